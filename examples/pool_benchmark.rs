@@ -0,0 +1,63 @@
+// 比较使用结点池和直接使用 Box 时链式结构反复 push/pop 的耗时
+// cargo run --release --example pool_benchmark
+
+use std::{hint::black_box, time::Instant};
+
+use rust_data_struct::linear_structure::{DoubleLinkedList, LinkedStack, SinglyLinkedList};
+
+const ROUNDS: usize = 2000;
+const BATCH: usize = 1000;
+
+fn measure(name: &str, mut run: impl FnMut()) -> f64 {
+    let start = Instant::now();
+    run();
+    let seconds = start.elapsed().as_secs_f64();
+    println!("{:<28}{:>10.2} ms", name, seconds * 1000.0);
+    seconds
+}
+
+fn linked_stack(capacity: usize) {
+    let mut stack = LinkedStack::with_pool_capacity(capacity);
+    for round in 0..ROUNDS {
+        for i in 0..BATCH {
+            stack.push(round + i);
+        }
+        while let Ok(value) = stack.pop() {
+            black_box(value);
+        }
+    }
+}
+
+fn singly_linked_list(capacity: usize) {
+    let mut list = SinglyLinkedList::with_pool_capacity(capacity);
+    for round in 0..ROUNDS {
+        for i in 0..BATCH {
+            list.push_front(round + i);
+        }
+        while list.delete(1).is_ok() {}
+    }
+}
+
+fn double_linked_list(capacity: usize) {
+    let mut list = DoubleLinkedList::with_pool_capacity(capacity);
+    for round in 0..ROUNDS {
+        for i in 0..BATCH {
+            list.push_back(round + i);
+        }
+        while let Some(value) = list.pop_back() {
+            black_box(value);
+        }
+    }
+}
+
+fn compare(name: &str, bench: fn(usize)) {
+    let boxed = measure(&format!("{} (Box)", name), || bench(0));
+    let pooled = measure(&format!("{} (pool)", name), || bench(BATCH));
+    println!("{:<28}{:>10.2}x\n", "speedup", boxed / pooled);
+}
+
+fn main() {
+    compare("LinkedStack", linked_stack);
+    compare("SinglyLinkedList", singly_linked_list);
+    compare("DoubleLinkedList", double_linked_list);
+}
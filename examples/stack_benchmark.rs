@@ -0,0 +1,33 @@
+// 测量 SequentialStack 压满再弹空的耗时随容量的变化
+// push/pop 是 O(1) 时，容量扩大 16 倍总耗时大约也是 16 倍；每次操作都要挪动元素的实现会接近 256 倍
+// cargo run --release --example stack_benchmark
+
+use std::{hint::black_box, time::{Duration, Instant}};
+
+use rust_data_struct::linear_structure::SequentialStack;
+
+// 多次运行取最短的时间，减少调度带来的误差
+fn fill_and_drain<const N: usize>() -> Duration {
+    let mut best = Duration::MAX;
+    for _ in 0..5 {
+        let mut stack: Box<SequentialStack<usize, N>> = Box::default();
+        let start = Instant::now();
+        for i in 0..N {
+            stack.push(i).unwrap();
+        }
+        while let Ok(value) = stack.pop() {
+            black_box(value);
+        }
+        best = best.min(start.elapsed());
+    }
+    best
+}
+
+fn main() {
+    let small = fill_and_drain::<1024>();
+    let large = fill_and_drain::<16384>();
+    let ratio = large.as_nanos() as f64 / small.as_nanos().max(1) as f64;
+    println!("{:<12}{:>12?}", "N = 1024", small);
+    println!("{:<12}{:>12?}", "N = 16384", large);
+    println!("ratio: {:.1}", ratio);
+}
@@ -0,0 +1 @@
+pub mod linear_structure;
@@ -0,0 +1,232 @@
+// 支持 O(1) 聚合查询的栈，例如随时取得栈中的最小值、最大值、和或者最大公约数
+// 除了保存元素的栈之外，还有一个辅助栈，辅助栈的第 i 个元素是原栈中前 i 个元素的聚合结果，
+// 这样 push/pop/peek/aggregate 都只需要访问两个栈的栈顶
+
+use std::{
+    marker::PhantomData,
+    ops::{Add, Rem},
+};
+
+use super::{LinkedStack, stack::Stack};
+
+// 聚合运算，只要求满足结合律：combine(combine(a, b), c) == combine(a, combine(b, c))，也就是半群
+// 空栈的聚合结果用 Err 表示，所以不需要单位元（例如泛型的最小值就没有单位元）
+pub trait Semigroup<T> {
+    fn combine(a: &T, b: &T) -> T;
+}
+
+// AggregateStack 的类型参数沿用 Monoid 这个名字，实际只用到了 Semigroup 的结合律
+pub use self::Semigroup as Monoid;
+
+pub struct Min;
+
+impl<T: Ord + Clone> Semigroup<T> for Min {
+    fn combine(a: &T, b: &T) -> T {
+        if b < a { b.clone() } else { a.clone() }
+    }
+}
+
+pub struct Max;
+
+impl<T: Ord + Clone> Semigroup<T> for Max {
+    fn combine(a: &T, b: &T) -> T {
+        if b > a { b.clone() } else { a.clone() }
+    }
+}
+
+pub struct Sum;
+
+impl<T: Add<Output = T> + Clone> Semigroup<T> for Sum {
+    fn combine(a: &T, b: &T) -> T {
+        a.clone() + b.clone()
+    }
+}
+
+// 最大公约数，T::default() 当作 0，适用于无符号整数
+pub struct Gcd;
+
+impl<T: Copy + PartialEq + Default + Rem<Output = T>> Semigroup<T> for Gcd {
+    fn combine(a: &T, b: &T) -> T {
+        let (mut a, mut b) = (*a, *b);
+        while b != T::default() {
+            let r = a % b;
+            a = b;
+            b = r;
+        }
+        a
+    }
+}
+
+// S 是底层使用的栈，默认是链栈，也可以是 SequentialStack<T, N>
+pub struct AggregateStack<T, M: Monoid<T>, S: Stack<T> = LinkedStack<T>> {
+    items: S,
+    aggregates: S,
+    marker: PhantomData<(T, M)>,
+}
+
+pub type MinStack<T, S = LinkedStack<T>> = AggregateStack<T, Min, S>;
+pub type MaxStack<T, S = LinkedStack<T>> = AggregateStack<T, Max, S>;
+
+impl<T: Clone, M: Monoid<T>, S: Stack<T> + Default> Default for AggregateStack<T, M, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, M: Monoid<T>, S: Stack<T> + Default> AggregateStack<T, M, S> {
+    pub fn new() -> Self {
+        Self { items: S::default(), aggregates: S::default(), marker: PhantomData }
+    }
+}
+
+impl<T: Clone, M: Monoid<T>, S: Stack<T>> AggregateStack<T, M, S> {
+    pub fn push(&mut self, element: T) -> Result<(), &'static str> {
+        let aggregate = match self.aggregates.peek() {
+            Ok(top) => M::combine(top, &element),
+            Err(_) => element.clone(),
+        };
+        self.items.push(element)?;
+        // 两个栈的元素个数始终相同，元素栈能放下，辅助栈也一定能放下
+        self.aggregates.push(aggregate)
+    }
+
+    pub fn pop(&mut self) -> Result<T, &'static str> {
+        let element = self.items.pop()?;
+        self.aggregates.pop()?;
+        Ok(element)
+    }
+
+    pub fn peek(&self) -> Result<&T, &'static str> {
+        self.items.peek()
+    }
+
+    // 栈中所有元素的聚合结果
+    pub fn aggregate(&self) -> Result<&T, &'static str> {
+        self.aggregates.peek()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn capacity(&self) -> Option<usize> {
+        self.items.capacity()
+    }
+}
+
+impl<T: Ord + Clone, S: Stack<T>> AggregateStack<T, Min, S> {
+    pub fn min(&self) -> Result<&T, &'static str> {
+        self.aggregate()
+    }
+}
+
+impl<T: Ord + Clone, S: Stack<T>> AggregateStack<T, Max, S> {
+    pub fn max(&self) -> Result<&T, &'static str> {
+        self.aggregate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linear_structure::SequentialStack;
+
+    #[test]
+    fn test_min_stack() {
+        let mut stack: MinStack<i32> = MinStack::new();
+        assert!(stack.min().is_err());
+        for (element, expected) in [(5, 5), (7, 5), (3, 3), (3, 3), (8, 3), (1, 1)] {
+            stack.push(element).unwrap();
+            assert_eq!(*stack.min().unwrap(), expected);
+            assert_eq!(*stack.peek().unwrap(), element);
+        }
+        for expected in [3, 3, 3, 5, 5] {
+            stack.pop().unwrap();
+            assert_eq!(*stack.min().unwrap(), expected);
+        }
+        assert_eq!(stack.pop().unwrap(), 5);
+        assert!(stack.is_empty());
+        assert!(stack.min().is_err());
+        assert!(stack.pop().is_err());
+    }
+
+    #[test]
+    fn test_max_stack_on_sequential_stack() {
+        let mut stack: MaxStack<String, SequentialStack<String, 3>> = MaxStack::new();
+        assert_eq!(stack.capacity(), Some(3));
+        stack.push("b".to_string()).unwrap();
+        stack.push("a".to_string()).unwrap();
+        stack.push("c".to_string()).unwrap();
+        assert_eq!(stack.max().unwrap(), "c");
+        assert_eq!(stack.push("z".to_string()), Err("Stack overflow!"));
+        assert_eq!(stack.len(), 3);
+        assert_eq!(stack.max().unwrap(), "c");
+        assert_eq!(stack.pop().unwrap(), "c");
+        assert_eq!(stack.max().unwrap(), "b");
+    }
+
+    #[test]
+    fn test_sum_and_gcd() {
+        let mut sums: AggregateStack<i64, Sum> = AggregateStack::new();
+        for i in 1..=100 {
+            sums.push(i).unwrap();
+        }
+        assert_eq!(*sums.aggregate().unwrap(), 5050);
+        sums.pop().unwrap();
+        assert_eq!(*sums.aggregate().unwrap(), 4950);
+
+        let mut gcds: AggregateStack<u64, Gcd, SequentialStack<u64, 8>> = AggregateStack::new();
+        gcds.push(48).unwrap();
+        gcds.push(36).unwrap();
+        assert_eq!(*gcds.aggregate().unwrap(), 12);
+        gcds.push(0).unwrap();
+        assert_eq!(*gcds.aggregate().unwrap(), 12);
+        gcds.push(10).unwrap();
+        assert_eq!(*gcds.aggregate().unwrap(), 2);
+        gcds.pop().unwrap();
+        assert_eq!(*gcds.aggregate().unwrap(), 12);
+    }
+
+    // 自定义的聚合运算既可以实现 Monoid 也可以实现 Semigroup，两者是同一个 trait
+    struct Concat;
+
+    impl Monoid<String> for Concat {
+        fn combine(a: &String, b: &String) -> String {
+            format!("{}{}", a, b)
+        }
+    }
+
+    #[test]
+    fn test_custom_monoid() {
+        let mut stack: AggregateStack<String, Concat> = AggregateStack::new();
+        for word in ["stack", "of", "words"] {
+            stack.push(word.to_string()).unwrap();
+        }
+        assert_eq!(stack.aggregate().unwrap(), "stackofwords");
+        stack.pop().unwrap();
+        assert_eq!(stack.aggregate().unwrap(), "stackof");
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let mut stack: MinStack<u32, SequentialStack<u32, 64>> = MinStack::default();
+        let mut model: Vec<u32> = Vec::new();
+        let mut seed: u32 = 7;
+        for _ in 0..1000 {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            if seed % 5 < 3 && model.len() < 64 {
+                let element = seed >> 24;
+                stack.push(element).unwrap();
+                model.push(element);
+            } else {
+                assert_eq!(stack.pop().ok(), model.pop());
+            }
+            assert_eq!(stack.min().ok(), model.iter().min());
+            assert_eq!(stack.len(), model.len());
+        }
+    }
+}
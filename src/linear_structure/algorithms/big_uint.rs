@@ -0,0 +1,624 @@
+// 任意精度的无符号整数，用来保存斐波那契数、阶乘、组合数这类很快就超出原生整数范围的结果
+// 以 2^32 为基数，limbs 按从低位到高位的顺序保存，并且最高位的 limb 不为 0，所以 0 就是空的 Vec
+// 乘法在两个数都比较长时使用 Karatsuba 算法，O(n^1.585)；除法使用 Knuth 的算法 D，O(n * m)
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, Mul, MulAssign, Rem, Sub, SubAssign},
+    str::FromStr,
+};
+
+// 两个数的 limb 个数都不少于这个值时才使用 Karatsuba，更短时普通的竖式乘法更快
+const KARATSUBA_THRESHOLD: usize = 32;
+
+// 转换十进制时每次处理 9 位，10^9 是小于 2^32 的最大的 10 的幂
+const DECIMAL_BASE: u32 = 1_000_000_000;
+const DECIMAL_DIGITS: usize = 9;
+
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub const fn zero() -> Self {
+        Self { limbs: Vec::new() }
+    }
+
+    pub fn one() -> Self {
+        Self { limbs: vec![1] }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    // 二进制位数，0 的位数是 0
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            None => 0,
+            Some(top) => self.limbs.len() as u64 * 32 - top.leading_zeros() as u64,
+        }
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        self.to_u128().and_then(|value| u64::try_from(value).ok())
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(self.limbs.iter().rev().fold(0u128, |acc, &limb| (acc << 32) | limb as u128))
+    }
+
+    fn from_limbs(mut limbs: Vec<u32>) -> Self {
+        trim(&mut limbs);
+        Self { limbs }
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        if *self < *other {
+            return None;
+        }
+        Some(Self::from_limbs(sub_slices(&self.limbs, &other.limbs)))
+    }
+
+    // 同时求商和余数，除数为 0 时返回错误
+    pub fn div_rem(&self, divisor: &Self) -> Result<(Self, Self), &'static str> {
+        match divisor.limbs.len() {
+            0 => Err("Division by zero!"),
+            1 => {
+                let (quotient, remainder) = self.div_rem_small(divisor.limbs[0])?;
+                Ok((quotient, Self::from(remainder)))
+            }
+            _ if *self < *divisor => Ok((Self::zero(), self.clone())),
+            _ => {
+                let (quotient, remainder) = div_rem_knuth(&self.limbs, &divisor.limbs);
+                Ok((Self::from_limbs(quotient), Self::from_limbs(remainder)))
+            }
+        }
+    }
+
+    // 除数只有一个 limb 时逐位做短除法，O(n)
+    pub fn div_rem_small(&self, divisor: u32) -> Result<(Self, u32), &'static str> {
+        if divisor == 0 {
+            return Err("Division by zero!");
+        }
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut remainder = 0u64;
+        for (i, &limb) in self.limbs.iter().enumerate().rev() {
+            let current = (remainder << 32) | limb as u64;
+            quotient[i] = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+        Ok((Self::from_limbs(quotient), remainder as u32))
+    }
+
+    // self = self * factor + addend，解析字符串时使用
+    fn mul_add_small(&mut self, factor: u32, addend: u32) {
+        let mut carry = addend as u64;
+        for limb in self.limbs.iter_mut() {
+            let current = *limb as u64 * factor as u64 + carry;
+            *limb = current as u32;
+            carry = current >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+        trim(&mut self.limbs);
+    }
+
+    // 支持 10 进制和 16 进制
+    pub fn from_str_radix(text: &str, radix: u32) -> Result<Self, &'static str> {
+        if radix != 10 && radix != 16 {
+            return Err("Unsupported radix!");
+        }
+        if text.is_empty() {
+            return Err("Empty number!");
+        }
+        if !text.chars().all(|c| c.is_digit(radix)) {
+            return Err("Invalid digit!");
+        }
+        // 前面已经检查过全部是合法的数字，都是 ASCII 字符
+        let value_of = |chunk: &[u8]| chunk.iter().fold(0u32, |acc, &c| acc * radix + (c as char).to_digit(radix).unwrap());
+        if radix == 16 {
+            // 从低位开始，每 8 个十六进制数字是一个 limb
+            return Ok(Self::from_limbs(text.as_bytes().rchunks(8).map(value_of).collect()));
+        }
+        let mut result = Self::zero();
+        for chunk in text.as_bytes().rchunks(DECIMAL_DIGITS).rev() {
+            result.mul_add_small(10u32.pow(chunk.len() as u32), value_of(chunk));
+        }
+        Ok(result)
+    }
+
+    fn to_decimal_string(&self) -> String {
+        if self.is_zero() {
+            return "0".to_string();
+        }
+        let mut chunks = Vec::new();
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            let (quotient, remainder) = rest.div_rem_small(DECIMAL_BASE).unwrap();
+            chunks.push(remainder);
+            rest = quotient;
+        }
+        let mut text = chunks.pop().unwrap().to_string();
+        for chunk in chunks.iter().rev() {
+            text.push_str(&format!("{:09}", chunk));
+        }
+        text
+    }
+
+    fn to_hex_string(&self, upper: bool) -> String {
+        let Some((top, rest)) = self.limbs.split_last() else {
+            return "0".to_string();
+        };
+        let mut text = format!("{:x}", top);
+        for limb in rest.iter().rev() {
+            text.push_str(&format!("{:08x}", limb));
+        }
+        if upper { text.to_uppercase() } else { text }
+    }
+}
+
+// 去掉高位的 0
+fn trim(limbs: &mut Vec<u32>) {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+}
+
+fn add_slices(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut result = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+    for (i, &limb) in long.iter().enumerate() {
+        let sum = limb as u64 + short.get(i).copied().unwrap_or(0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+    result
+}
+
+// 调用者保证 a >= b
+fn sub_slices(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &limb) in a.iter().enumerate() {
+        let mut diff = limb as i64 - b.get(i).copied().unwrap_or(0) as i64 - borrow;
+        borrow = 0;
+        if diff < 0 {
+            diff += 1 << 32;
+            borrow = 1;
+        }
+        result.push(diff as u32);
+    }
+    debug_assert_eq!(borrow, 0);
+    result
+}
+
+fn schoolbook_mul(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            let current = x as u64 * y as u64 + result[i + j] as u64 + carry;
+            result[i + j] = current as u32;
+            carry = current >> 32;
+        }
+        result[i + b.len()] = carry as u32;
+    }
+    result
+}
+
+// 把 a 和 b 都从第 half 个 limb 处拆开：a = a1 * B^half + a0，b = b1 * B^half + b0
+// a * b = z2 * B^(2 half) + z1 * B^half + z0，其中 z1 = (a0 + a1)(b0 + b1) - z0 - z2，只需要三次乘法
+fn mul_slices(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.len().min(b.len()) < KARATSUBA_THRESHOLD {
+        let mut result = schoolbook_mul(a, b);
+        trim(&mut result);
+        return result;
+    }
+    let half = a.len().min(b.len()) / 2;
+    let (a0, a1) = a.split_at(half);
+    let (b0, b1) = b.split_at(half);
+    let z0 = mul_slices(strip(a0), strip(b0));
+    let z2 = mul_slices(a1, b1);
+    let mut z1 = mul_slices(&add_slices(a0, a1), &add_slices(b0, b1));
+    z1 = sub_slices(&z1, &z0);
+    trim(&mut z1);
+    z1 = sub_slices(&z1, &z2);
+
+    let mut result = vec![0u32; a.len() + b.len() + 1];
+    add_into(&mut result, &z0, 0);
+    add_into(&mut result, &z1, half);
+    add_into(&mut result, &z2, 2 * half);
+    trim(&mut result);
+    result
+}
+
+// 去掉切片高位的 0，拆分之后低半部分的高位可能是 0
+fn strip(limbs: &[u32]) -> &[u32] {
+    let len = limbs.iter().rposition(|&limb| limb != 0).map_or(0, |i| i + 1);
+    &limbs[..len]
+}
+
+// target += value * B^offset，调用者保证 target 足够长
+fn add_into(target: &mut [u32], value: &[u32], offset: usize) {
+    let mut carry = 0u64;
+    let mut i = offset;
+    for &limb in value {
+        let sum = target[i] as u64 + limb as u64 + carry;
+        target[i] = sum as u32;
+        carry = sum >> 32;
+        i += 1;
+    }
+    while carry > 0 {
+        let sum = target[i] as u64 + carry;
+        target[i] = sum as u32;
+        carry = sum >> 32;
+        i += 1;
+    }
+}
+
+// Knuth 算法 D，要求 v 至少有两个 limb 且 u >= v
+// 先把 u 和 v 左移，使 v 的最高位是 1，这样每一位商的估计值最多比真实值大 2
+fn div_rem_knuth(u: &[u32], v: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    let n = v.len();
+    let m = u.len() - n;
+    let shift = v[n - 1].leading_zeros();
+    let vn = shift_left(v, shift, n);
+    let mut un = shift_left(u, shift, u.len() + 1);
+    let mut quotient = vec![0u32; m + 1];
+    let base = 1u64 << 32;
+
+    for j in (0..=m).rev() {
+        let numerator = ((un[j + n] as u64) << 32) | un[j + n - 1] as u64;
+        let mut q_hat = numerator / vn[n - 1] as u64;
+        let mut r_hat = numerator % vn[n - 1] as u64;
+        while q_hat >= base || q_hat * vn[n - 2] as u64 > ((r_hat << 32) | un[j + n - 2] as u64) {
+            q_hat -= 1;
+            r_hat += vn[n - 1] as u64;
+            if r_hat >= base {
+                break;
+            }
+        }
+
+        // un[j..=j+n] -= q_hat * vn
+        let mut borrow = 0i64;
+        for i in 0..n {
+            let product = q_hat * vn[i] as u64;
+            let diff = un[i + j] as i64 - borrow - (product & 0xFFFF_FFFF) as i64;
+            un[i + j] = diff as u32;
+            borrow = (product >> 32) as i64 - (diff >> 32);
+        }
+        let diff = un[j + n] as i64 - borrow;
+        un[j + n] = diff as u32;
+
+        quotient[j] = q_hat as u32;
+        // 估计值大了 1，把多减的 vn 加回来
+        if diff < 0 {
+            quotient[j] -= 1;
+            let mut carry = 0u64;
+            for i in 0..n {
+                let sum = un[i + j] as u64 + vn[i] as u64 + carry;
+                un[i + j] = sum as u32;
+                carry = sum >> 32;
+            }
+            un[j + n] = un[j + n].wrapping_add(carry as u32);
+        }
+    }
+
+    let remainder = shift_right(&un[..n], shift);
+    (quotient, remainder)
+}
+
+// 左移 shift 位（shift < 32），结果补齐到 len 个 limb
+fn shift_left(limbs: &[u32], shift: u32, len: usize) -> Vec<u32> {
+    let mut result = vec![0u32; len];
+    let mut carry = 0u32;
+    for (i, &limb) in limbs.iter().enumerate() {
+        result[i] = (limb << shift) | carry;
+        carry = if shift == 0 { 0 } else { limb >> (32 - shift) };
+    }
+    if limbs.len() < len {
+        result[limbs.len()] = carry;
+    }
+    result
+}
+
+fn shift_right(limbs: &[u32], shift: u32) -> Vec<u32> {
+    let mut result = vec![0u32; limbs.len()];
+    for i in 0..limbs.len() {
+        let high = if shift == 0 { 0 } else { limbs.get(i + 1).map_or(0, |&next| next << (32 - shift)) };
+        result[i] = (limbs[i] >> shift) | high;
+    }
+    result
+}
+
+macro_rules! impl_from_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigUint {
+                fn from(value: $t) -> Self {
+                    let mut value = value as u128;
+                    let mut limbs = Vec::new();
+                    while value > 0 {
+                        limbs.push(value as u32);
+                        value >>= 32;
+                    }
+                    Self { limbs }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_unsigned!(u8, u16, u32, u64, u128, usize);
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs.len().cmp(&other.limbs.len()).then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, rhs: &BigUint) -> BigUint {
+        BigUint { limbs: add_slices(&self.limbs, &rhs.limbs) }
+    }
+}
+
+// 和原生无符号整数一样，结果为负时 panic，不想 panic 就用 checked_sub
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn sub(self, rhs: &BigUint) -> BigUint {
+        self.checked_sub(rhs).expect("attempt to subtract with overflow")
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        if self.is_zero() || rhs.is_zero() {
+            return BigUint::zero();
+        }
+        BigUint { limbs: mul_slices(&self.limbs, &rhs.limbs) }
+    }
+}
+
+// 除数为 0 时 panic，不想 panic 就用 div_rem
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).expect("attempt to divide by zero").0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).expect("attempt to calculate the remainder with a divisor of zero").1
+    }
+}
+
+// 其余的所有权组合都转发给引用版本
+macro_rules! forward_binary_op {
+    ($($trait:ident $method:ident),*) => {
+        $(
+            impl $trait<BigUint> for BigUint {
+                type Output = BigUint;
+
+                fn $method(self, rhs: BigUint) -> BigUint {
+                    (&self).$method(&rhs)
+                }
+            }
+
+            impl $trait<&BigUint> for BigUint {
+                type Output = BigUint;
+
+                fn $method(self, rhs: &BigUint) -> BigUint {
+                    (&self).$method(rhs)
+                }
+            }
+
+            impl $trait<BigUint> for &BigUint {
+                type Output = BigUint;
+
+                fn $method(self, rhs: BigUint) -> BigUint {
+                    self.$method(&rhs)
+                }
+            }
+        )*
+    };
+}
+
+forward_binary_op!(Add add, Sub sub, Mul mul, Div div, Rem rem);
+
+macro_rules! forward_assign_op {
+    ($($trait:ident $method:ident $op:ident),*) => {
+        $(
+            impl $trait<&BigUint> for BigUint {
+                fn $method(&mut self, rhs: &BigUint) {
+                    *self = (&*self).$op(rhs);
+                }
+            }
+
+            impl $trait<BigUint> for BigUint {
+                fn $method(&mut self, rhs: BigUint) {
+                    *self = (&*self).$op(&rhs);
+                }
+            }
+        )*
+    };
+}
+
+forward_assign_op!(AddAssign add_assign add, SubAssign sub_assign sub, MulAssign mul_assign mul);
+
+impl Sum for BigUint {
+    fn sum<I: Iterator<Item = BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::zero(), |acc, x| acc + x)
+    }
+}
+
+impl Product for BigUint {
+    fn product<I: Iterator<Item = BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::one(), |acc, x| acc * x)
+    }
+}
+
+// 十进制，"0x" 或 "0X" 开头时按十六进制解析
+impl FromStr for BigUint {
+    type Err = &'static str;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+            Some(hex) => Self::from_str_radix(hex, 16),
+            None => Self::from_str_radix(text, 10),
+        }
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "", &self.to_decimal_string())
+    }
+}
+
+impl fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::LowerHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0x", &self.to_hex_string(false))
+    }
+}
+
+impl fmt::UpperHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0x", &self.to_hex_string(true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self) -> u64 {
+            self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            self.0 >> 16
+        }
+
+        fn big(&mut self, limbs: usize) -> BigUint {
+            BigUint::from_limbs((0..limbs).map(|_| self.next() as u32).collect())
+        }
+    }
+
+    #[test]
+    fn test_matches_u128() {
+        let mut random = Random(1);
+        for _ in 0..2000 {
+            let (a, b) = (random.next() as u128 * random.next() as u128, random.next() as u128);
+            let (x, y) = (BigUint::from(a), BigUint::from(b));
+            assert_eq!((&x + &y).to_u128(), Some(a + b));
+            assert_eq!((&x * &y).to_u128(), a.checked_mul(b));
+            assert_eq!(x.checked_sub(&y).and_then(|d| d.to_u128()), a.checked_sub(b));
+            assert_eq!(x.cmp(&y), a.cmp(&b));
+            let quotient = x.div_rem(&y).ok().map(|(q, r)| (q.to_u128().unwrap(), r.to_u128().unwrap()));
+            assert_eq!(quotient, a.checked_div(b).zip(a.checked_rem(b)));
+        }
+        assert_eq!(BigUint::from(u128::MAX).to_u128(), Some(u128::MAX));
+        assert_eq!((BigUint::from(u128::MAX) + BigUint::one()).to_u128(), None);
+        assert_eq!(BigUint::from(0u32), BigUint::zero());
+    }
+
+    #[test]
+    fn test_karatsuba_matches_schoolbook() {
+        let mut random = Random(2);
+        for (m, n) in [(32, 32), (40, 100), (257, 300), (64, 1000), (500, 31)] {
+            let (a, b) = (random.big(m), random.big(n));
+            let mut expected = schoolbook_mul(&a.limbs, &b.limbs);
+            trim(&mut expected);
+            assert_eq!((&a * &b).limbs, expected);
+        }
+        // 全是 0xFFFFFFFF 时进位最多
+        let max = BigUint::from_limbs(vec![u32::MAX; 200]);
+        let square = &max * &max;
+        let mut expected = schoolbook_mul(&max.limbs, &max.limbs);
+        trim(&mut expected);
+        assert_eq!(square.limbs, expected);
+        assert_eq!(square.bits(), 200 * 32 * 2);
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let mut random = Random(3);
+        for (m, n) in [(2, 2), (10, 3), (50, 2), (100, 99), (80, 40), (300, 120)] {
+            let (u, v) = (random.big(m), random.big(n));
+            let (q, r) = u.div_rem(&v).unwrap();
+            assert!(r < v);
+            assert_eq!(&q * &v + &r, u);
+        }
+        // 商的估计值需要修正、需要加回除数的边界情况
+        let u = BigUint::from_limbs(vec![0, 0, 0x8000_0000, 0x7FFF_FFFF]);
+        let v = BigUint::from_limbs(vec![1, 0, 0x8000_0000]);
+        let (q, r) = u.div_rem(&v).unwrap();
+        assert_eq!(&q * &v + &r, u);
+        assert!(r < v);
+
+        assert_eq!(u.div_rem(&BigUint::zero()), Err("Division by zero!"));
+        assert_eq!(u.div_rem_small(0), Err("Division by zero!"));
+        assert_eq!(BigUint::from(100u32).div_rem(&u).unwrap(), (BigUint::zero(), BigUint::from(100u32)));
+    }
+
+    #[test]
+    fn test_parse_and_format() {
+        let text = "123456789012345678901234567890123456789012345678901234567890";
+        let x: BigUint = text.parse().unwrap();
+        assert_eq!(x.to_string(), text);
+        assert_eq!(BigUint::from_str_radix(&format!("{:x}", x), 16), Ok(x.clone()));
+        assert_eq!(format!("{:#x}", x).parse::<BigUint>(), Ok(x.clone()));
+        assert_eq!(format!("{:#X}", x).parse::<BigUint>(), Ok(x.clone()));
+        assert_eq!(format!("{:x}", BigUint::from(0x1_0000_0000_u64)), "100000000");
+        assert_eq!(format!("{:>5}", BigUint::from(42u32)), "   42");
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!("000000000000000000001".parse(), Ok(BigUint::one()));
+        assert_eq!("".parse::<BigUint>(), Err("Empty number!"));
+        assert_eq!("0x".parse::<BigUint>(), Err("Empty number!"));
+        assert_eq!("12a".parse::<BigUint>(), Err("Invalid digit!"));
+        assert_eq!("+1".parse::<BigUint>(), Err("Invalid digit!"));
+        assert_eq!(BigUint::from_str_radix("101", 2), Err("Unsupported radix!"));
+    }
+
+    #[test]
+    fn test_factorial() {
+        let factorial: BigUint = (1..=30u32).map(BigUint::from).product();
+        assert_eq!(factorial.to_string(), "265252859812191058636308480000000");
+        let sum: BigUint = (1..=100u32).map(BigUint::from).sum();
+        assert_eq!(sum, BigUint::from(5050u32));
+    }
+}
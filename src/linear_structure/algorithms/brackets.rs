@@ -0,0 +1,644 @@
+// 栈的经典应用：括号匹配
+// 检查 ()[]{}<> 等定界符是否成对出现，字符串字面量和注释中的定界符会被忽略，注释、字符串的写法可以按语言配置
+// 输入按字节流式处理，除了定界符栈之外只使用常数大小的内存，所以可以直接校验很大的文件
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    io::{self, Read},
+};
+
+use crate::linear_structure::LinkedStack;
+
+// 一种语言的定界符、字符串和注释写法，所有字符都必须是 ASCII 字符，否则 BracketValidator::new 返回 InvalidLanguage
+#[derive(Debug, Clone)]
+pub struct Language {
+    // (开定界符, 闭定界符)
+    pub pairs: Vec<(char, char)>,
+    // 字符串的引号，字符串内部的定界符会被忽略，没有闭合的字符串会被报告
+    pub quotes: Vec<char>,
+    // 字符串中的转义字符
+    pub escape: Option<char>,
+    pub line_comments: Vec<&'static str>,
+    pub block_comment: Option<(&'static str, &'static str)>,
+    // 块注释是否可以嵌套，例如 Rust 的 /* /* */ */
+    pub nested_comments: bool,
+    // ' 后面是一个字符（或者转义序列）再跟一个 ' 时当作字符字面量，否则当作生命周期或者标签，例如 Rust 的 '(' 和 'a
+    pub char_literals: bool,
+    // r"..." 和 r#"..."# 形式的原始字符串，里面没有转义，遇到 " 加上同样多的 # 才结束
+    pub raw_strings: bool,
+}
+
+impl Language {
+    // 普通文本：四种括号加上双引号，没有注释
+    pub fn plain() -> Self {
+        Self {
+            pairs: vec![('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')],
+            quotes: vec!['"'],
+            escape: None,
+            line_comments: vec![],
+            block_comment: None,
+            nested_comments: false,
+            char_literals: false,
+            raw_strings: false,
+        }
+    }
+
+    // Rust 中 < > 也是比较运算符，所以不参与检查；' 还用于生命周期，不能简单地当作引号
+    // b"..."、br"..." 的前缀 b 按普通字符处理，不影响结果
+    pub fn rust() -> Self {
+        Self {
+            pairs: vec![('(', ')'), ('[', ']'), ('{', '}')],
+            quotes: vec!['"'],
+            escape: Some('\\'),
+            line_comments: vec!["//"],
+            block_comment: Some(("/*", "*/")),
+            nested_comments: true,
+            char_literals: true,
+            raw_strings: true,
+        }
+    }
+
+    pub fn c() -> Self {
+        Self {
+            pairs: vec![('(', ')'), ('[', ']'), ('{', '}')],
+            quotes: vec!['"', '\''],
+            escape: Some('\\'),
+            line_comments: vec!["//"],
+            block_comment: Some(("/*", "*/")),
+            nested_comments: false,
+            char_literals: false,
+            raw_strings: false,
+        }
+    }
+
+    pub fn python() -> Self {
+        Self {
+            pairs: vec![('(', ')'), ('[', ']'), ('{', '}')],
+            quotes: vec!['"', '\''],
+            escape: Some('\\'),
+            line_comments: vec!["#"],
+            block_comment: None,
+            nested_comments: false,
+            char_literals: false,
+            raw_strings: false,
+        }
+    }
+
+    pub fn json() -> Self {
+        Self {
+            pairs: vec![('[', ']'), ('{', '}')],
+            quotes: vec!['"'],
+            escape: Some('\\'),
+            line_comments: vec![],
+            block_comment: None,
+            nested_comments: false,
+            char_literals: false,
+            raw_strings: false,
+        }
+    }
+}
+
+// 行号和列号都从 1 开始，列号按字符（而不是字节）计数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Diagnostic {
+    // 闭定界符和最近的开定界符不匹配
+    Mismatched { found: char, at: Location, expected: char, opened_at: Location },
+    // 闭定界符前面没有任何开定界符
+    Unexpected { found: char, at: Location },
+    // 直到输入结束都没有闭合的开定界符
+    Unclosed { open: char, at: Location, expected: char },
+    UnterminatedString { quote: char, at: Location },
+    UnterminatedComment { at: Location },
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::Mismatched { found, at, expected, opened_at } => {
+                write!(f, "{}: expected '{}' to close the delimiter opened at {}, found '{}'", at, expected, opened_at, found)
+            }
+            Diagnostic::Unexpected { found, at } => write!(f, "{}: unexpected closing delimiter '{}'", at, found),
+            Diagnostic::Unclosed { open, at, expected } => write!(f, "{}: '{}' is never closed, expected '{}'", at, open, expected),
+            Diagnostic::UnterminatedString { quote, at } => write!(f, "{}: unterminated string starting with {}", at, quote),
+            Diagnostic::UnterminatedComment { at } => write!(f, "{}: unterminated block comment", at),
+        }
+    }
+}
+
+// Language 中出现了非 ASCII 的定界符、引号或者注释标记
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidLanguage {
+    pub found: char,
+}
+
+impl fmt::Display for InvalidLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "delimiters, quotes and comment markers must be ASCII, got {:?}", self.found)
+    }
+}
+
+impl std::error::Error for InvalidLanguage {}
+
+#[derive(Debug, Clone, Copy)]
+enum State {
+    Code,
+    String { quote: u8, start: Location, escaped: bool },
+    // closing 是最近一个 " 之后已经出现的 # 的个数，达到 hashes 时原始字符串结束
+    RawString { start: Location, hashes: usize, closing: Option<usize> },
+    LineComment,
+    BlockComment { start: Location, depth: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Marker {
+    LineComment,
+    BlockComment,
+}
+
+// 暂存的字节开头是什么，决定了接下来要消耗多少字节
+enum Prefix {
+    Comment(usize, Marker),
+    // 完整的字符字面量，例如 '(' 或者 '中'，整体跳过
+    CharLiteral(usize),
+    // 带转义的字符字面量，例如 '\''，按照以 ' 为引号的字符串处理
+    EscapedChar,
+    RawString { len: usize, hashes: usize },
+    Plain,
+}
+
+// 和 Rust 一样，原始字符串最多使用 255 个 #，这样暂存的字节数有上限
+const MAX_RAW_HASHES: usize = 255;
+
+// 栈中保存的开定界符以及它的位置
+struct OpenDelimiter {
+    open: u8,
+    at: Location,
+}
+
+pub struct BracketValidator {
+    pairs: Vec<(u8, u8)>,
+    quotes: Vec<u8>,
+    escape: Option<u8>,
+    markers: Vec<(Vec<u8>, Marker)>,
+    block_start: Vec<u8>,
+    block_end: Vec<u8>,
+    nested_comments: bool,
+    char_literals: bool,
+    raw_strings: bool,
+    stack: LinkedStack<OpenDelimiter>,
+    state: State,
+    // 可能是注释、字符字面量或者原始字符串开头的若干字节，要等后面的字节到来才能确定，长度有固定的上限
+    pending: VecDeque<(u8, Location)>,
+    // 块注释中最近的几个字节，用来识别注释的结束（以及嵌套的开始），长度不超过两个注释标记中较长的那个
+    window: VecDeque<u8>,
+    line: usize,
+    column: usize,
+    last: Location,
+    diagnostics: Vec<Diagnostic>,
+}
+
+fn ascii(c: char) -> Result<u8, InvalidLanguage> {
+    if c.is_ascii() { Ok(c as u8) } else { Err(InvalidLanguage { found: c }) }
+}
+
+fn ascii_str(s: &str) -> Result<Vec<u8>, InvalidLanguage> {
+    s.chars().map(ascii).collect()
+}
+
+fn ends_with(window: &VecDeque<u8>, pattern: &[u8]) -> bool {
+    window.len() >= pattern.len() && window.iter().rev().zip(pattern.iter().rev()).all(|(a, b)| a == b)
+}
+
+// 根据 UTF-8 的首字节得到这个字符占几个字节
+fn utf8_len(lead: u8) -> usize {
+    match lead.leading_ones() {
+        2 => 2,
+        3 => 3,
+        4 => 4,
+        _ => 1,
+    }
+}
+
+impl BracketValidator {
+    // 配置在这里一次性检查，之后按字节处理时不会再出错
+    pub fn new(language: &Language) -> Result<Self, InvalidLanguage> {
+        let mut markers = language.line_comments.iter().map(|m| Ok((ascii_str(m)?, Marker::LineComment))).collect::<Result<Vec<_>, _>>()?;
+        let (mut block_start, mut block_end) = (Vec::new(), Vec::new());
+        if let Some((start, end)) = language.block_comment {
+            block_start = ascii_str(start)?;
+            block_end = ascii_str(end)?;
+            markers.push((block_start.clone(), Marker::BlockComment));
+        }
+        Ok(Self {
+            pairs: language.pairs.iter().map(|&(open, close)| Ok((ascii(open)?, ascii(close)?))).collect::<Result<_, _>>()?,
+            quotes: language.quotes.iter().copied().map(ascii).collect::<Result<_, _>>()?,
+            escape: language.escape.map(ascii).transpose()?,
+            markers,
+            block_start,
+            block_end,
+            nested_comments: language.nested_comments,
+            char_literals: language.char_literals,
+            raw_strings: language.raw_strings,
+            stack: LinkedStack::new(),
+            state: State::Code,
+            pending: VecDeque::new(),
+            window: VecDeque::new(),
+            line: 1,
+            column: 1,
+            last: Location { line: 1, column: 1 },
+            diagnostics: Vec::new(),
+        })
+    }
+
+    // 输入下一段字节，可以多次调用，字节可以在任意位置（包括 UTF-8 字符中间）被切开
+    pub fn feed(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            // UTF-8 的后续字节不占列号
+            let at = if byte & 0xC0 == 0x80 {
+                self.last
+            } else {
+                let at = Location { line: self.line, column: self.column };
+                self.column += 1;
+                at
+            };
+            if byte == b'\n' {
+                self.line += 1;
+                self.column = 1;
+            }
+            self.last = at;
+            self.step(byte, at);
+        }
+    }
+
+    // 取出目前为止发现的问题，适合边读边报告
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    // 输入结束，报告没有闭合的字符串、注释和定界符
+    pub fn finish(mut self) -> Vec<Diagnostic> {
+        self.drain_pending(true);
+        match self.state {
+            State::String { quote, start, .. } => {
+                self.diagnostics.push(Diagnostic::UnterminatedString { quote: quote as char, at: start });
+            }
+            State::RawString { start, .. } => self.diagnostics.push(Diagnostic::UnterminatedString { quote: '"', at: start }),
+            State::BlockComment { start, .. } => self.diagnostics.push(Diagnostic::UnterminatedComment { at: start }),
+            State::Code | State::LineComment => {}
+        }
+        let mut unclosed = Vec::new();
+        while let Ok(open) = self.stack.pop() {
+            let expected = self.closer_of(open.open);
+            unclosed.push(Diagnostic::Unclosed { open: open.open as char, at: open.at, expected: expected as char });
+        }
+        // 出栈的顺序是从内到外，按出现的先后报告
+        self.diagnostics.extend(unclosed.into_iter().rev());
+        self.diagnostics
+    }
+
+    fn step(&mut self, byte: u8, at: Location) {
+        match self.state {
+            State::Code => {
+                self.pending.push_back((byte, at));
+                self.drain_pending(false);
+            }
+            State::String { quote, start, escaped } => {
+                if escaped {
+                    self.state = State::String { quote, start, escaped: false };
+                } else if Some(byte) == self.escape {
+                    self.state = State::String { quote, start, escaped: true };
+                } else if byte == quote {
+                    self.state = State::Code;
+                }
+            }
+            State::RawString { start, hashes, closing } => {
+                let closing = match (closing, byte) {
+                    (_, b'"') => Some(0),
+                    (Some(seen), b'#') => Some(seen + 1),
+                    _ => None,
+                };
+                self.state = if closing == Some(hashes) { State::Code } else { State::RawString { start, hashes, closing } };
+            }
+            State::LineComment => {
+                if byte == b'\n' {
+                    self.state = State::Code;
+                }
+            }
+            State::BlockComment { start, depth } => {
+                self.window.push_back(byte);
+                if ends_with(&self.window, &self.block_end) {
+                    self.window.clear();
+                    self.state = if depth == 1 { State::Code } else { State::BlockComment { start, depth: depth - 1 } };
+                } else if self.nested_comments && ends_with(&self.window, &self.block_start) {
+                    self.window.clear();
+                    self.state = State::BlockComment { start, depth: depth + 1 };
+                } else if self.window.len() > self.block_end.len().max(self.block_start.len()) {
+                    self.window.pop_front();
+                }
+            }
+        }
+    }
+
+    // 暂存的第 i 个字节
+    fn pending_byte(&self, i: usize) -> Option<u8> {
+        self.pending.get(i).map(|&(byte, _)| byte)
+    }
+
+    // 判断暂存的字节是以什么开头的，还需要更多字节才能确定时返回 None
+    // 最长匹配的注释标记优先，eof 为 true 时不再等待后续字节
+    fn prefix(&self, eof: bool) -> Option<Prefix> {
+        let len = self.pending.len();
+        let is_prefix_of = |m: &[u8]| self.pending.iter().zip(m).all(|(&(b, _), &c)| b == c);
+        if !eof && self.markers.iter().any(|(m, _)| m.len() > len && is_prefix_of(m)) {
+            return None;
+        }
+        let longest = self.markers.iter().filter(|(m, _)| m.len() <= len && is_prefix_of(m)).max_by_key(|(m, _)| m.len());
+        if let Some((m, kind)) = longest {
+            return Some(Prefix::Comment(m.len(), *kind));
+        }
+        // 后续字节还没有到来时，eof 为 true 说明不会再有了，按普通字节处理
+        let wait = if eof { Some(Prefix::Plain) } else { None };
+        match self.pending_byte(0) {
+            Some(b'\'') if self.char_literals => match self.pending_byte(1) {
+                None => wait,
+                Some(byte) if Some(byte) == self.escape => Some(Prefix::EscapedChar),
+                Some(b'\'') => Some(Prefix::Plain),
+                // ' 加上一个完整的字符之后紧跟 ' 才是字符字面量，否则是生命周期
+                Some(lead) => match self.pending_byte(1 + utf8_len(lead)) {
+                    None => wait,
+                    Some(b'\'') => Some(Prefix::CharLiteral(2 + utf8_len(lead))),
+                    Some(_) => Some(Prefix::Plain),
+                },
+            },
+            Some(b'r') if self.raw_strings => {
+                let hashes = self.pending.iter().skip(1).take_while(|&&(b, _)| b == b'#').count();
+                match self.pending_byte(1 + hashes) {
+                    None if hashes < MAX_RAW_HASHES => wait,
+                    Some(b'"') => Some(Prefix::RawString { len: 2 + hashes, hashes }),
+                    // r#ident 形式的原始标识符，或者只是普通的 r
+                    _ => Some(Prefix::Plain),
+                }
+            }
+            _ => Some(Prefix::Plain),
+        }
+    }
+
+    // 处理暂存的字节
+    fn drain_pending(&mut self, eof: bool) {
+        while !self.pending.is_empty() && matches!(self.state, State::Code) {
+            let Some(prefix) = self.prefix(eof) else {
+                return;
+            };
+            let start = self.pending[0].1;
+            match prefix {
+                Prefix::Comment(len, kind) => {
+                    self.pending.drain(..len);
+                    self.state = match kind {
+                        Marker::LineComment => State::LineComment,
+                        Marker::BlockComment => State::BlockComment { start, depth: 1 },
+                    };
+                    self.window.clear();
+                }
+                Prefix::CharLiteral(len) => {
+                    self.pending.drain(..len);
+                }
+                Prefix::EscapedChar => {
+                    self.pending.pop_front();
+                    self.state = State::String { quote: b'\'', start, escaped: false };
+                }
+                Prefix::RawString { len, hashes } => {
+                    self.pending.drain(..len);
+                    self.state = State::RawString { start, hashes, closing: None };
+                }
+                Prefix::Plain => {
+                    let (byte, at) = self.pending.pop_front().unwrap();
+                    self.plain(byte, at);
+                }
+            }
+        }
+        // 进入了字符串或者注释，剩下的字节交给新的状态处理
+        if !self.pending.is_empty() && !matches!(self.state, State::Code) {
+            for (byte, at) in std::mem::take(&mut self.pending) {
+                self.step(byte, at);
+            }
+        }
+    }
+
+    // 代码中的普通字节
+    fn plain(&mut self, byte: u8, at: Location) {
+        if self.quotes.contains(&byte) {
+            self.state = State::String { quote: byte, start: at, escaped: false };
+        } else if self.pairs.iter().any(|&(open, _)| open == byte) {
+            // 链栈没有容量上限，push 不会失败
+            self.stack.push(OpenDelimiter { open: byte, at });
+        } else if let Some(&(open, close)) = self.pairs.iter().find(|&&(_, close)| close == byte) {
+            match self.stack.peek() {
+                Ok(top) if top.open == open => {
+                    let _ = self.stack.pop();
+                }
+                Ok(top) => {
+                    let expected = self.closer_of(top.open);
+                    self.diagnostics.push(Diagnostic::Mismatched { found: close as char, at, expected: expected as char, opened_at: top.at });
+                    let _ = self.stack.pop();
+                }
+                Err(_) => self.diagnostics.push(Diagnostic::Unexpected { found: close as char, at }),
+            }
+        }
+    }
+
+    fn closer_of(&self, open: u8) -> u8 {
+        self.pairs.iter().find(|&&(o, _)| o == open).map(|&(_, close)| close).unwrap()
+    }
+}
+
+pub fn validate_str(text: &str, language: &Language) -> Result<Vec<Diagnostic>, InvalidLanguage> {
+    let mut validator = BracketValidator::new(language)?;
+    validator.feed(text.as_bytes());
+    Ok(validator.finish())
+}
+
+// 以固定大小的缓冲区分块读取，配置有误时返回 InvalidInput 类型的错误
+pub fn validate_reader<R: Read>(mut reader: R, language: &Language) -> io::Result<Vec<Diagnostic>> {
+    let mut validator = BracketValidator::new(language).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let mut buffer = [0u8; 8192];
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => validator.feed(&buffer[..n]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(validator.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(line: usize, column: usize) -> Location {
+        Location { line, column }
+    }
+
+    fn validate(text: &str, language: &Language) -> Vec<Diagnostic> {
+        validate_str(text, language).unwrap()
+    }
+
+    #[test]
+    fn test_balanced() {
+        assert!(validate("", &Language::plain()).is_empty());
+        assert!(validate("a(b[c]{d<e>})", &Language::plain()).is_empty());
+        assert!(validate("fn main() { let v = vec![1, 2]; }", &Language::rust()).is_empty());
+    }
+
+    #[test]
+    fn test_mismatched_and_unexpected() {
+        let diagnostics = validate("(\n  [ }\n)", &Language::plain());
+        assert_eq!(diagnostics, vec![Diagnostic::Mismatched { found: '}', at: at(2, 5), expected: ']', opened_at: at(2, 3) }]);
+
+        let diagnostics = validate("a)", &Language::plain());
+        assert_eq!(diagnostics, vec![Diagnostic::Unexpected { found: ')', at: at(1, 2) }]);
+    }
+
+    #[test]
+    fn test_unclosed_reported_outermost_first() {
+        let diagnostics = validate("{ (\n[", &Language::plain());
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::Unclosed { open: '{', at: at(1, 1), expected: '}' },
+                Diagnostic::Unclosed { open: '(', at: at(1, 3), expected: ')' },
+                Diagnostic::Unclosed { open: '[', at: at(2, 1), expected: ']' },
+            ]
+        );
+        assert_eq!(diagnostics[0].to_string(), "1:1: '{' is never closed, expected '}'");
+    }
+
+    #[test]
+    fn test_strings_are_ignored() {
+        let rust = Language::rust();
+        assert!(validate(r#"f("(", "\")")"#, &rust).is_empty());
+        assert_eq!(
+            validate("let s = \"abc(;\n", &rust),
+            vec![Diagnostic::UnterminatedString { quote: '"', at: at(1, 9) }]
+        );
+        // Rust 的 ' 用于生命周期，不当作引号
+        assert!(validate("fn f<'a>(x: &'a str) {}", &rust).is_empty());
+        assert!(validate("c = ')'", &Language::c()).is_empty());
+    }
+
+    #[test]
+    fn test_rust_char_literals() {
+        let rust = Language::rust();
+        assert!(validate("let q = '\"';", &rust).is_empty());
+        assert!(validate("if c == '(' || c == '}' || c == '[' {}", &rust).is_empty());
+        assert!(validate(r"let e = ['\'', '\\', '\u{7b}', b')'];", &rust).is_empty());
+        assert!(validate("let c = '括'; f(c)", &rust).is_empty());
+        // 生命周期和标签后面没有紧跟 '，不是字符字面量
+        assert!(validate("fn f<'a, 'b>(x: &'a str, y: &'b str) { 'outer: loop { break 'outer; } }", &rust).is_empty());
+        assert!(validate("impl<'a> S<'a> { fn g(&'a self) -> char { 'x' } }", &rust).is_empty());
+        assert_eq!(validate("let c = '\\", &rust), vec![Diagnostic::UnterminatedString { quote: '\'', at: at(1, 9) }]);
+        // 字符字面量之外的括号照常检查
+        assert_eq!(validate("f(')'", &rust), vec![Diagnostic::Unclosed { open: '(', at: at(1, 2), expected: ')' }]);
+    }
+
+    #[test]
+    fn test_rust_raw_strings() {
+        let rust = Language::rust();
+        assert!(validate(r##"let s = r#")"#;"##, &rust).is_empty());
+        assert!(validate(r###"f(r"(", br##"a "# ] "##)"###, &rust).is_empty());
+        // r#ident 是原始标识符，后面的括号照常检查
+        assert_eq!(validate("r#type(", &rust), vec![Diagnostic::Unclosed { open: '(', at: at(1, 7), expected: ')' }]);
+        assert!(validate("let bar = (r);", &rust).is_empty());
+        assert_eq!(
+            validate("x(r#\"unterminated\" ) ", &rust),
+            vec![
+                Diagnostic::UnterminatedString { quote: '"', at: at(1, 3) },
+                Diagnostic::Unclosed { open: '(', at: at(1, 2), expected: ')' },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_comments_are_ignored() {
+        let rust = Language::rust();
+        assert!(validate("f(1) // ) ] }\ng(2)", &rust).is_empty());
+        assert!(validate("f(/* ) */ 1)", &rust).is_empty());
+        assert!(validate("/* outer /* inner ( */ still comment ] */ x()", &rust).is_empty());
+        assert_eq!(validate("x /* (", &rust), vec![Diagnostic::UnterminatedComment { at: at(1, 3) }]);
+        // C 的块注释不能嵌套
+        assert_eq!(
+            validate("/* /* */ ) */", &Language::c()),
+            vec![Diagnostic::Unexpected { found: ')', at: at(1, 10) }]
+        );
+        assert!(validate("print(x)  # )\n", &Language::python()).is_empty());
+        // / 本身不是注释，后面的 ( 要正常处理
+        assert_eq!(
+            validate("a / (b", &rust),
+            vec![Diagnostic::Unclosed { open: '(', at: at(1, 5), expected: ')' }]
+        );
+    }
+
+    #[test]
+    fn test_columns_count_characters() {
+        let diagnostics = validate("\"括号\" 中文 ]", &Language::plain());
+        assert_eq!(diagnostics, vec![Diagnostic::Unexpected { found: ']', at: at(1, 9) }]);
+    }
+
+    #[test]
+    fn test_chunked_input_matches_whole_input() {
+        let text = "fn f<'a>() {\n    // comment (\n    let s = \"]\"; /* ) */ let c = '}';\n    let r = r#\")\"#; g(h[0)];\n}\n(";
+        let expected = validate(text, &Language::rust());
+        assert_eq!(expected.len(), 3);
+        for chunk_size in 1..=7 {
+            let mut validator = BracketValidator::new(&Language::rust()).unwrap();
+            for chunk in text.as_bytes().chunks(chunk_size) {
+                validator.feed(chunk);
+            }
+            assert_eq!(validator.finish(), expected, "chunk size {}", chunk_size);
+        }
+        assert_eq!(validate_reader(text.as_bytes(), &Language::rust()).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_large_input() {
+        // 一千层嵌套，中间夹着大量注释
+        let mut text = String::new();
+        for _ in 0..1000 {
+            text.push_str("{ // }\n");
+        }
+        for _ in 0..1000 {
+            text.push('}');
+        }
+        let json = "[".repeat(500) + &"]".repeat(500);
+        assert!(validate(&text, &Language::rust()).is_empty());
+        assert!(validate(&json, &Language::json()).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_language() {
+        let mut language = Language::plain();
+        language.pairs.push(('«', '»'));
+        assert_eq!(BracketValidator::new(&language).err(), Some(InvalidLanguage { found: '«' }));
+        assert_eq!(validate_str("«»", &language), Err(InvalidLanguage { found: '«' }));
+        let error = validate_reader("«»".as_bytes(), &language).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+
+        let mut language = Language::rust();
+        language.line_comments.push("——");
+        assert_eq!(validate_str("", &language), Err(InvalidLanguage { found: '—' }));
+    }
+}
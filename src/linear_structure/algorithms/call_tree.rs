@@ -0,0 +1,315 @@
+// 记录递归算法的调用树，用于教学和调试
+// 被跟踪的函数通过 tracer.call(...) 发起递归调用，每次调用都会成为树上的一个结点，
+// 结点保存参数、返回值、深度，以及它是不是一个之前已经算过的重复子问题
+// 调用树可以导出成缩进文本、Graphviz DOT 和 JSON，CallStats 给出总调用次数和不同子问题的个数，
+// 两者的差距就是记忆化能省下的调用；trace_memoized 同时打开缓存，重复的子问题只留下一个叶子结点
+
+use std::{collections::HashMap, fmt, hash::Hash};
+
+pub struct CallNode<A, V> {
+    args: A,
+    result: Option<V>,
+    depth: usize,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    duplicate_of: Option<usize>,
+}
+
+impl<A, V> CallNode<A, V> {
+    pub fn args(&self) -> &A {
+        &self.args
+    }
+
+    pub fn result(&self) -> &V {
+        self.result.as_ref().expect("call has returned")
+    }
+
+    // 根结点的深度是 0
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+
+    pub fn children(&self) -> &[usize] {
+        &self.children
+    }
+
+    // 第一次用相同参数调用的结点编号
+    pub fn duplicate_of(&self) -> Option<usize> {
+        self.duplicate_of
+    }
+
+    pub fn is_duplicate(&self) -> bool {
+        self.duplicate_of.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallStats {
+    pub total: usize,
+    pub distinct: usize,
+    pub duplicates: usize,
+    pub max_depth: usize,
+}
+
+impl fmt::Display for CallStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} calls, {} distinct ({} duplicate), max depth {}", self.total, self.distinct, self.duplicates, self.max_depth)
+    }
+}
+
+// 结点按调用的先后顺序保存，也就是先序遍历的顺序，0 号结点是根
+pub struct CallTree<A, V> {
+    name: String,
+    nodes: Vec<CallNode<A, V>>,
+}
+
+// 执行被跟踪函数时使用的记录器
+pub struct Tracer<'f, A, V> {
+    function: &'f dyn Fn(&mut Tracer<'f, A, V>, A) -> V,
+    nodes: Vec<CallNode<A, V>>,
+    // 当前正在执行的调用
+    current: Option<usize>,
+    // 每组参数第一次出现的结点
+    first: HashMap<A, usize>,
+    memoized: bool,
+}
+
+impl<A: Eq + Hash + Clone, V: Clone> Tracer<'_, A, V> {
+    pub fn call(&mut self, args: A) -> V {
+        let index = self.nodes.len();
+        let duplicate_of = self.first.get(&args).copied();
+        let depth = self.current.map_or(0, |parent| self.nodes[parent].depth + 1);
+        self.nodes.push(CallNode { args: args.clone(), result: None, depth, parent: self.current, children: Vec::new(), duplicate_of });
+        if let Some(parent) = self.current {
+            self.nodes[parent].children.push(index);
+        }
+        if duplicate_of.is_none() {
+            self.first.insert(args.clone(), index);
+        }
+
+        // 记忆化时重复的子问题直接使用第一次的结果，不再展开
+        let cached = duplicate_of.filter(|_| self.memoized).and_then(|first| self.nodes[first].result.clone());
+        let result = match cached {
+            Some(result) => result,
+            None => {
+                let parent = self.current.replace(index);
+                let result = (self.function)(self, args);
+                self.current = parent;
+                result
+            }
+        };
+        self.nodes[index].result = Some(result.clone());
+        result
+    }
+}
+
+pub fn trace<A: Eq + Hash + Clone, V: Clone>(name: &str, args: A, function: impl Fn(&mut Tracer<'_, A, V>, A) -> V) -> CallTree<A, V> {
+    run(name, args, &function, false)
+}
+
+pub fn trace_memoized<A: Eq + Hash + Clone, V: Clone>(name: &str, args: A, function: impl Fn(&mut Tracer<'_, A, V>, A) -> V) -> CallTree<A, V> {
+    run(name, args, &function, true)
+}
+
+fn run<'f, A: Eq + Hash + Clone, V: Clone>(name: &str, args: A, function: &'f dyn Fn(&mut Tracer<'f, A, V>, A) -> V, memoized: bool) -> CallTree<A, V> {
+    let mut tracer = Tracer { function, nodes: Vec::new(), current: None, first: HashMap::new(), memoized };
+    tracer.call(args);
+    CallTree { name: name.to_string(), nodes: tracer.nodes }
+}
+
+impl<A, V> CallTree<A, V> {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn nodes(&self) -> &[CallNode<A, V>] {
+        &self.nodes
+    }
+
+    pub fn root(&self) -> &CallNode<A, V> {
+        &self.nodes[0]
+    }
+
+    pub fn stats(&self) -> CallStats {
+        let total = self.nodes.len();
+        let duplicates = self.nodes.iter().filter(|node| node.is_duplicate()).count();
+        let max_depth = self.nodes.iter().map(|node| node.depth).max().unwrap_or(0);
+        CallStats { total, distinct: total - duplicates, duplicates, max_depth }
+    }
+}
+
+impl<A: fmt::Debug, V: fmt::Debug> CallTree<A, V> {
+    // fib(5)，参数本身是元组时不再额外加括号：hanoi(3, 'A', 'C', 'B')
+    fn call_label(&self, node: &CallNode<A, V>) -> String {
+        let args = format!("{:?}", node.args);
+        if args.starts_with('(') && args.ends_with(')') { format!("{}{}", self.name, args) } else { format!("{}({})", self.name, args) }
+    }
+
+    // 每层缩进两个空格：
+    // fib(2) = 1
+    //   fib(1) = 1
+    //   fib(0) = 0
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for node in &self.nodes {
+            text.push_str(&"  ".repeat(node.depth));
+            text.push_str(&format!("{} = {:?}", self.call_label(node), node.result()));
+            if node.is_duplicate() {
+                text.push_str(" [duplicate]");
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    // 重复的子问题用灰色填充
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph calls {\n    node [shape=box];\n");
+        for (i, node) in self.nodes.iter().enumerate() {
+            let label = escape(&format!("{} = {:?}", self.call_label(node), node.result()));
+            let style = if node.is_duplicate() { ", style=filled, fillcolor=lightgrey" } else { "" };
+            dot.push_str(&format!("    n{} [label=\"{}\"{}];\n", i, label, style));
+        }
+        for (i, node) in self.nodes.iter().enumerate() {
+            for child in &node.children {
+                dot.push_str(&format!("    n{} -> n{};\n", i, child));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    // 嵌套的 JSON 对象，参数和返回值用 Debug 格式的字符串表示
+    // 按先序顺序输出，深度变小时补上相应个数的右括号，所以不需要递归
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        let mut previous_depth: Option<usize> = None;
+        for node in &self.nodes {
+            if let Some(previous) = previous_depth
+                && node.depth <= previous
+            {
+                json.push_str(&"]}".repeat(previous - node.depth + 1));
+                json.push(',');
+            }
+            json.push_str(&format!(
+                "{{\"call\":\"{}\",\"args\":\"{}\",\"result\":\"{}\",\"depth\":{},\"duplicate\":{},\"children\":[",
+                escape(&self.call_label(node)),
+                escape(&format!("{:?}", node.args)),
+                escape(&format!("{:?}", node.result())),
+                node.depth,
+                node.is_duplicate()
+            ));
+            previous_depth = Some(node.depth);
+        }
+        if let Some(previous) = previous_depth {
+            json.push_str(&"]}".repeat(previous + 1));
+        }
+        json
+    }
+}
+
+// DOT 和 JSON 的字符串都使用反斜杠转义
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fib(tracer: &mut Tracer<'_, u64, u64>, n: u64) -> u64 {
+        if n < 2 { n } else { tracer.call(n - 1) + tracer.call(n - 2) }
+    }
+
+    #[test]
+    fn test_text_and_stats() {
+        let tree = trace("fib", 4, fib);
+        let expected = "\
+fib(4) = 3
+  fib(3) = 2
+    fib(2) = 1
+      fib(1) = 1
+      fib(0) = 0
+    fib(1) = 1 [duplicate]
+  fib(2) = 1 [duplicate]
+    fib(1) = 1 [duplicate]
+    fib(0) = 0 [duplicate]
+";
+        assert_eq!(tree.to_text(), expected);
+        assert_eq!(tree.stats(), CallStats { total: 9, distinct: 5, duplicates: 4, max_depth: 3 });
+        assert_eq!(tree.root().children(), [1, 6]);
+        assert_eq!(tree.nodes()[6].duplicate_of(), Some(2));
+        assert_eq!(tree.nodes()[6].parent(), Some(0));
+    }
+
+    #[test]
+    fn test_memoization_savings() {
+        let plain = trace("fib", 20, fib);
+        let memoized = trace_memoized("fib", 20, fib);
+        assert_eq!(*plain.root().result(), 6765);
+        assert_eq!(*memoized.root().result(), 6765);
+        assert_eq!(plain.stats().to_string(), "21891 calls, 21 distinct (21870 duplicate), max depth 19");
+        // 记忆化之后每个重复的子问题只剩一次不展开的调用
+        assert_eq!(memoized.stats().to_string(), "39 calls, 21 distinct (18 duplicate), max depth 19");
+        assert!(memoized.nodes().iter().filter(|node| node.is_duplicate()).all(|node| node.children().is_empty()));
+    }
+
+    #[test]
+    fn test_dot() {
+        let tree = trace("fib", 2, fib);
+        let expected = "\
+digraph calls {
+    node [shape=box];
+    n0 [label=\"fib(2) = 1\"];
+    n1 [label=\"fib(1) = 1\"];
+    n2 [label=\"fib(0) = 0\"];
+    n0 -> n1;
+    n0 -> n2;
+}
+";
+        assert_eq!(tree.to_dot(), expected);
+        assert!(trace("fib", 3, fib).to_dot().contains("n4 [label=\"fib(1) = 1\", style=filled, fillcolor=lightgrey];"));
+    }
+
+    #[test]
+    fn test_json() {
+        let tree = trace("fib", 2, fib);
+        let expected = concat!(
+            r#"{"call":"fib(2)","args":"2","result":"1","depth":0,"duplicate":false,"children":["#,
+            r#"{"call":"fib(1)","args":"1","result":"1","depth":1,"duplicate":false,"children":[]},"#,
+            r#"{"call":"fib(0)","args":"0","result":"0","depth":1,"duplicate":false,"children":[]}]}"#
+        );
+        assert_eq!(tree.to_json(), expected);
+
+        // 字符串参数需要转义
+        let tree = trace("echo", "say \"hi\"".to_string(), |_, s| s.len());
+        assert_eq!(tree.to_json(), r#"{"call":"echo(\"say \\\"hi\\\"\")","args":"\"say \\\"hi\\\"\"","result":"8","depth":0,"duplicate":false,"children":[]}"#);
+    }
+
+    #[test]
+    fn test_hanoi() {
+        // 返回移动的步数，参数是 (n, from, to, via)
+        let tree = trace("hanoi", (3u32, 'A', 'C', 'B'), |tracer, (n, from, to, via)| {
+            if n == 0 { 0u64 } else { tracer.call((n - 1, from, via, to)) + 1 + tracer.call((n - 1, via, to, from)) }
+        });
+        assert_eq!(*tree.root().result(), 7);
+        assert_eq!(tree.stats().total, 15);
+        assert!(tree.to_text().starts_with("hanoi(3, 'A', 'C', 'B') = 7\n  hanoi(2, 'A', 'B', 'C') = 3\n"));
+        // 三个柱子只有 6 种排列，n = 1 和 n = 0 的调用各自只有 3 种不同的参数
+        assert_eq!(tree.stats().distinct, 1 + 2 + 3 + 3);
+    }
+}
@@ -0,0 +1,495 @@
+// 栈的经典应用：中缀表达式求值
+// 先把中缀表达式拆成记号，再用调度场算法（shunting-yard）转换成后缀表达式，最后用栈对后缀表达式求值
+// 支持 + - * / % ^、一元负号、括号，以及 max(a, b) 这样的函数调用，^ 是右结合的，并且优先级高于一元负号，所以 -2^2 = -4
+
+use std::fmt;
+
+use crate::linear_structure::{LinkedStack, Stack};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+    // 一元负号
+    Neg,
+}
+
+impl Operator {
+    fn precedence(self) -> u8 {
+        match self {
+            Operator::Add | Operator::Sub => 1,
+            Operator::Mul | Operator::Div | Operator::Rem => 2,
+            Operator::Neg => 3,
+            Operator::Pow => 4,
+        }
+    }
+
+    fn is_right_associative(self) -> bool {
+        matches!(self, Operator::Pow | Operator::Neg)
+    }
+
+    fn arity(self) -> usize {
+        match self {
+            Operator::Neg => 1,
+            _ => 2,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Operator::Add => "+",
+            Operator::Sub => "-",
+            Operator::Mul => "*",
+            Operator::Div => "/",
+            Operator::Rem => "%",
+            Operator::Pow => "^",
+            Operator::Neg => "neg",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Function {
+    Max,
+    Min,
+    Abs,
+    Sqrt,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "max" => Some(Function::Max),
+            "min" => Some(Function::Min),
+            "abs" => Some(Function::Abs),
+            "sqrt" => Some(Function::Sqrt),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Function::Max => "max",
+            Function::Min => "min",
+            Function::Abs => "abs",
+            Function::Sqrt => "sqrt",
+        }
+    }
+
+    fn arity(self) -> usize {
+        match self {
+            Function::Max | Function::Min => 2,
+            Function::Abs | Function::Sqrt => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Token {
+    Number(f64),
+    Operator(Operator),
+    Function(Function),
+    LeftParen,
+    RightParen,
+    Comma,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(value) => write!(f, "{}", value),
+            Token::Operator(op) => write!(f, "{}", op.symbol()),
+            Token::Function(func) => write!(f, "{}", func.name()),
+            Token::LeftParen => write!(f, "("),
+            Token::RightParen => write!(f, ")"),
+            Token::Comma => write!(f, ","),
+        }
+    }
+}
+
+// 记号以及它在输入中的位置（按字符计数，从 0 开始），出错时用来定位
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionedToken {
+    pub token: Token,
+    pub position: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpressionError {
+    pub position: usize,
+    pub message: &'static str,
+}
+
+impl ExpressionError {
+    fn new(position: usize, message: &'static str) -> Self {
+        Self { position, message }
+    }
+}
+
+impl fmt::Display for ExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ExpressionError {}
+
+// 把输入拆成记号，这一步还不区分一元负号和减号
+pub fn tokenize(input: &str) -> Result<Vec<PositionedToken>, ExpressionError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().enumerate().peekable();
+    while let Some((position, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '0'..='9' | '.' => {
+                let mut text = String::from(c);
+                while let Some(&(_, next)) = chars.peek() {
+                    if next.is_ascii_digit() || next == '.' {
+                        text.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let value = text.parse().map_err(|_| ExpressionError::new(position, "Invalid number"))?;
+                Token::Number(value)
+            }
+            c if c.is_alphabetic() => {
+                let mut name = String::from(c);
+                while let Some(&(_, next)) = chars.peek() {
+                    if next.is_alphanumeric() || next == '_' {
+                        name.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let func = Function::from_name(&name).ok_or(ExpressionError::new(position, "Unknown function"))?;
+                Token::Function(func)
+            }
+            '+' => Token::Operator(Operator::Add),
+            '-' => Token::Operator(Operator::Sub),
+            '*' => Token::Operator(Operator::Mul),
+            '/' => Token::Operator(Operator::Div),
+            '%' => Token::Operator(Operator::Rem),
+            '^' => Token::Operator(Operator::Pow),
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            ',' => Token::Comma,
+            _ => return Err(ExpressionError::new(position, "Unexpected character")),
+        };
+        tokens.push(PositionedToken { token, position });
+    }
+    Ok(tokens)
+}
+
+// 调度场算法：中缀转后缀
+pub fn to_postfix(input: &str) -> Result<Vec<PositionedToken>, ExpressionError> {
+    let tokens = tokenize(input)?;
+    let mut output = Vec::with_capacity(tokens.len());
+    // 运算符栈，栈里只会出现运算符、函数和左括号
+    let mut operators: LinkedStack<PositionedToken> = LinkedStack::new();
+    // 每一层函数调用已经读到的参数个数，普通括号也会压入一层，用 None 表示
+    let mut arguments: LinkedStack<Option<usize>> = LinkedStack::new();
+    // 下一个记号是否应该是操作数，用来识别一元负号
+    let mut expect_operand = true;
+
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(current) = tokens.next() {
+        let position = current.position;
+        // 栈本身的错误（例如有界栈溢出）记在当前记号的位置
+        let at = |message| ExpressionError::new(position, message);
+        match current.token {
+            Token::Number(_) => {
+                if !expect_operand {
+                    return Err(ExpressionError::new(position, "Missing operator"));
+                }
+                output.push(current);
+                expect_operand = false;
+            }
+            Token::Function(_) => {
+                if !expect_operand {
+                    return Err(ExpressionError::new(position, "Missing operator"));
+                }
+                if !matches!(tokens.peek(), Some(PositionedToken { token: Token::LeftParen, .. })) {
+                    return Err(ExpressionError::new(position, "Missing '(' after function"));
+                }
+                Stack::push(&mut operators, current).map_err(at)?;
+            }
+            Token::LeftParen => {
+                if !expect_operand {
+                    return Err(ExpressionError::new(position, "Missing operator"));
+                }
+                let is_call = matches!(Stack::peek(&operators), Ok(PositionedToken { token: Token::Function(_), .. }));
+                Stack::push(&mut arguments, if is_call { Some(1) } else { None }).map_err(at)?;
+                Stack::push(&mut operators, current).map_err(at)?;
+            }
+            Token::Comma => {
+                if expect_operand {
+                    return Err(ExpressionError::new(position, "Missing operand"));
+                }
+                pop_until_left_paren(&mut operators, &mut output, position, "Unexpected ','")?;
+                match Stack::peek_mut(&mut arguments) {
+                    Ok(Some(count)) => *count += 1,
+                    _ => return Err(ExpressionError::new(position, "Unexpected ','")),
+                }
+                expect_operand = true;
+            }
+            Token::RightParen => {
+                if expect_operand {
+                    return Err(ExpressionError::new(position, "Missing operand"));
+                }
+                pop_until_left_paren(&mut operators, &mut output, position, "Mismatched ')'")?;
+                Stack::pop(&mut operators).map_err(at)?;
+                if let Some(count) = Stack::pop(&mut arguments).map_err(at)? {
+                    let func = Stack::pop(&mut operators).map_err(at)?;
+                    if let Token::Function(f) = func.token
+                        && f.arity() != count
+                    {
+                        return Err(ExpressionError::new(func.position, "Wrong number of arguments"));
+                    }
+                    output.push(func);
+                }
+            }
+            Token::Operator(op) => {
+                if expect_operand {
+                    match op {
+                        // 一元正号不影响结果，直接忽略
+                        Operator::Add => continue,
+                        Operator::Sub => {
+                            let neg = PositionedToken { token: Token::Operator(Operator::Neg), position };
+                            Stack::push(&mut operators, neg).map_err(at)?;
+                            continue;
+                        }
+                        _ => return Err(ExpressionError::new(position, "Missing operand")),
+                    }
+                }
+                while let Ok(top) = Stack::peek(&operators) {
+                    let Token::Operator(top_op) = top.token else { break };
+                    let higher = top_op.precedence() > op.precedence();
+                    let equal_left = top_op.precedence() == op.precedence() && !op.is_right_associative();
+                    if !(higher || equal_left) {
+                        break;
+                    }
+                    output.push(Stack::pop(&mut operators).map_err(at)?);
+                }
+                Stack::push(&mut operators, current).map_err(at)?;
+                expect_operand = true;
+            }
+        }
+    }
+
+    if expect_operand {
+        return Err(ExpressionError::new(input.chars().count(), "Missing operand"));
+    }
+    while let Ok(top) = Stack::pop(&mut operators) {
+        match top.token {
+            Token::LeftParen => return Err(ExpressionError::new(top.position, "Mismatched '('")),
+            _ => output.push(top),
+        }
+    }
+    Ok(output)
+}
+
+fn pop_until_left_paren(
+    operators: &mut LinkedStack<PositionedToken>,
+    output: &mut Vec<PositionedToken>,
+    position: usize,
+    message: &'static str,
+) -> Result<(), ExpressionError> {
+    loop {
+        match Stack::peek(operators) {
+            Ok(PositionedToken { token: Token::LeftParen, .. }) => return Ok(()),
+            Ok(_) => output.push(Stack::pop(operators).map_err(|message| ExpressionError::new(position, message))?),
+            Err(_) => return Err(ExpressionError::new(position, message)),
+        }
+    }
+}
+
+// 中缀转前缀：先得到后缀表达式，再用一个栈把每个运算符和它的操作数重新组合成“运算符在前”的形式
+pub fn to_prefix(input: &str) -> Result<Vec<PositionedToken>, ExpressionError> {
+    let postfix = to_postfix(input)?;
+    let mut stack: LinkedStack<Vec<PositionedToken>> = LinkedStack::new();
+    for current in postfix {
+        let position = current.position;
+        let arity = match current.token {
+            Token::Operator(op) => op.arity(),
+            Token::Function(f) => f.arity(),
+            _ => 0,
+        };
+        let mut operands = Vec::with_capacity(arity);
+        for _ in 0..arity {
+            operands.push(Stack::pop(&mut stack).map_err(|_| ExpressionError::new(position, "Missing operand"))?);
+        }
+        let mut expression = vec![current];
+        for operand in operands.into_iter().rev() {
+            expression.extend(operand);
+        }
+        Stack::push(&mut stack, expression).map_err(|message| ExpressionError::new(position, message))?;
+    }
+    Stack::pop(&mut stack).map_err(|_| ExpressionError::new(0, "Empty expression"))
+}
+
+// 把记号序列格式化成以空格分隔的字符串，例如 "1 2 3 * +"
+pub fn format_tokens(tokens: &[PositionedToken]) -> String {
+    tokens.iter().map(|t| t.token.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+// 对后缀表达式求值，操作数栈由调用者提供，可以是 LinkedStack 也可以是 SequentialStack
+pub fn evaluate_postfix<S: Stack<f64>>(postfix: &[PositionedToken], mut stack: S) -> Result<f64, ExpressionError> {
+    for current in postfix {
+        let position = current.position;
+        let missing = |_| ExpressionError::new(position, "Missing operand");
+        let value = match current.token {
+            Token::Number(value) => value,
+            Token::Operator(Operator::Neg) => -stack.pop().map_err(missing)?,
+            Token::Operator(op) => {
+                let b = stack.pop().map_err(missing)?;
+                let a = stack.pop().map_err(missing)?;
+                match op {
+                    Operator::Add => a + b,
+                    Operator::Sub => a - b,
+                    Operator::Mul => a * b,
+                    Operator::Div | Operator::Rem if b == 0.0 => {
+                        return Err(ExpressionError::new(position, "Division by zero"));
+                    }
+                    Operator::Div => a / b,
+                    Operator::Rem => a % b,
+                    Operator::Pow => a.powf(b),
+                    Operator::Neg => unreachable!(),
+                }
+            }
+            Token::Function(func) => {
+                let b = stack.pop().map_err(missing)?;
+                match func {
+                    Function::Abs => b.abs(),
+                    Function::Sqrt if b < 0.0 => {
+                        return Err(ExpressionError::new(position, "Square root of a negative number"));
+                    }
+                    Function::Sqrt => b.sqrt(),
+                    Function::Max => stack.pop().map_err(missing)?.max(b),
+                    Function::Min => stack.pop().map_err(missing)?.min(b),
+                }
+            }
+            _ => return Err(ExpressionError::new(position, "Unexpected token in postfix expression")),
+        };
+        stack.push(value).map_err(|message| ExpressionError::new(position, message))?;
+    }
+    let result = stack.pop().map_err(|_| ExpressionError::new(0, "Empty expression"))?;
+    if !stack.is_empty() {
+        return Err(ExpressionError::new(0, "Missing operator"));
+    }
+    Ok(result)
+}
+
+// 使用指定的栈对中缀表达式求值
+pub fn evaluate_with<S: Stack<f64>>(input: &str, stack: S) -> Result<f64, ExpressionError> {
+    evaluate_postfix(&to_postfix(input)?, stack)
+}
+
+// 使用链栈对中缀表达式求值
+pub fn evaluate(input: &str) -> Result<f64, ExpressionError> {
+    evaluate_with(input, LinkedStack::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linear_structure::SequentialStack;
+
+    fn postfix(input: &str) -> String {
+        format_tokens(&to_postfix(input).unwrap())
+    }
+
+    fn prefix(input: &str) -> String {
+        format_tokens(&to_prefix(input).unwrap())
+    }
+
+    #[test]
+    fn test_precedence_and_associativity() {
+        assert_eq!(postfix("1 + 2 * 3"), "1 2 3 * +");
+        assert_eq!(postfix("(1 + 2) * 3"), "1 2 + 3 *");
+        assert_eq!(postfix("8 - 3 - 2"), "8 3 - 2 -");
+        assert_eq!(postfix("2 ^ 3 ^ 2"), "2 3 2 ^ ^");
+        assert_eq!(postfix("7 % 4 / 2"), "7 4 % 2 /");
+        assert_eq!(evaluate("8 - 3 - 2").unwrap(), 3.0);
+        assert_eq!(evaluate("2 ^ 3 ^ 2").unwrap(), 512.0);
+        assert_eq!(evaluate("1 + 2 * 3").unwrap(), 7.0);
+        assert_eq!(evaluate("(1 + 2) * 3").unwrap(), 9.0);
+        assert_eq!(evaluate("1.5 * 4").unwrap(), 6.0);
+    }
+
+    #[test]
+    fn test_unary_minus() {
+        assert_eq!(postfix("-2 ^ 2"), "2 2 ^ neg");
+        assert_eq!(postfix("-2 * 3"), "2 neg 3 *");
+        assert_eq!(evaluate("-2 ^ 2").unwrap(), -4.0);
+        assert_eq!(evaluate("(-2) ^ 2").unwrap(), 4.0);
+        assert_eq!(evaluate("2 ^ -1").unwrap(), 0.5);
+        assert_eq!(evaluate("3 - -2").unwrap(), 5.0);
+        assert_eq!(evaluate("--3").unwrap(), 3.0);
+        assert_eq!(evaluate("+3 * -(1 + 1)").unwrap(), -6.0);
+    }
+
+    #[test]
+    fn test_functions() {
+        assert_eq!(postfix("max(1, 2 + 3)"), "1 2 3 + max");
+        assert_eq!(evaluate("max(1, 2 + 3) * 2").unwrap(), 10.0);
+        assert_eq!(evaluate("min(max(1, 4), abs(-3))").unwrap(), 3.0);
+        assert_eq!(evaluate("sqrt(16) + 1").unwrap(), 5.0);
+        assert_eq!(evaluate("-abs(-2)").unwrap(), -2.0);
+    }
+
+    #[test]
+    fn test_prefix() {
+        assert_eq!(prefix("1 + 2 * 3"), "+ 1 * 2 3");
+        assert_eq!(prefix("(1 + 2) * 3"), "* + 1 2 3");
+        assert_eq!(prefix("8 - 3 - 2"), "- - 8 3 2");
+        assert_eq!(prefix("2 ^ 3 ^ 2"), "^ 2 ^ 3 2");
+        assert_eq!(prefix("-max(1, 2)"), "neg max 1 2");
+    }
+
+    #[test]
+    fn test_evaluate_on_different_stacks() {
+        let input = "max(2, 3) ^ 2 - 10 % 4";
+        let linked = evaluate_with(input, LinkedStack::new()).unwrap();
+        let sequential = evaluate_with(input, SequentialStack::<f64, 8>::new()).unwrap();
+        assert_eq!(linked, 7.0);
+        assert_eq!(sequential, 7.0);
+
+        // 顺序栈容量不够时报告溢出的位置
+        let err = evaluate_with("1 + (2 + (3 + 4))", SequentialStack::<f64, 3>::new()).unwrap_err();
+        assert_eq!(err.message, "Stack overflow!");
+        assert_eq!(err.position, 14);
+    }
+
+    #[test]
+    fn test_error_positions() {
+        let cases = [
+            ("1 + ", 4, "Missing operand"),
+            ("1 + * 2", 4, "Missing operand"),
+            ("1 2", 2, "Missing operator"),
+            ("(1 + 2", 0, "Mismatched '('"),
+            ("1 + 2)", 5, "Mismatched ')'"),
+            ("1 + $", 4, "Unexpected character"),
+            ("foo(1)", 0, "Unknown function"),
+            ("max(1)", 0, "Wrong number of arguments"),
+            ("abs(1, 2)", 0, "Wrong number of arguments"),
+            ("(1, 2)", 2, "Unexpected ','"),
+            ("1.2.3", 0, "Invalid number"),
+            ("4 / (2 - 2)", 2, "Division by zero"),
+            ("sqrt(-1)", 0, "Square root of a negative number"),
+            ("", 0, "Missing operand"),
+            ("max 1", 0, "Missing '(' after function"),
+        ];
+        for (input, position, message) in cases {
+            let err = evaluate(input).unwrap_err();
+            assert_eq!(err, ExpressionError { position, message }, "input: {:?}", input);
+        }
+        assert_eq!(evaluate("1 +").unwrap_err().to_string(), "Missing operand at position 3");
+    }
+}
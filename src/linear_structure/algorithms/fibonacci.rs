@@ -7,16 +7,233 @@
  * @Description: 这是默认设置,请设置`customMade`, 打开koroFileHeader查看配置 进行设置: https://github.com/OBKoro1/koro1FileHeader/wiki/%E9%85%8D%E7%BD%AE
  */
 //  经典递归算法解决的问题斐波那契数列
-pub fn fibonacci(n: usize) -> Result<usize, &'static str>{
-    if n >= 50 {
-        Err("问题规模太大了")
-    } else {
-        if n == 0 {
-            Ok(0)
-        } else if n == 1 {
-            Ok(1)
+// 朴素递归需要 O(φ^n) 次调用，这里给出两种 O(log n) 的解法：
+// 1. 快速倍增：F(2k) = F(k) * (2F(k+1) - F(k))，F(2k+1) = F(k)^2 + F(k+1)^2
+// 2. 矩阵快速幂：[[1, 1], [1, 0]]^n = [[F(n+1), F(n)], [F(n), F(n-1)]]
+// 所有运算都做溢出检查，并且只计算真正需要的项，所以恰好在第一个放不下的 n 处返回 OverflowError
+
+use std::fmt;
+
+use super::big_uint::BigUint;
+
+// 可以用来计算斐波那契数的无符号整数类型
+pub trait FibonacciInt: Copy + Eq + fmt::Debug {
+    const ZERO: Self;
+    const ONE: Self;
+    const BITS: u32;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_fibonacci_int {
+    ($($t:ty),*) => {
+        $(
+            impl FibonacciInt for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const BITS: u32 = <$t>::BITS;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_fibonacci_int!(u32, u64, u128);
+
+// F(n) 超出了目标类型的范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverflowError {
+    pub n: u64,
+    pub bits: u32,
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "F({}) does not fit in u{}", self.n, self.bits)
+    }
+}
+
+impl std::error::Error for OverflowError {}
+
+pub fn fibonacci(n: u64) -> Result<u64, OverflowError> {
+    fast_doubling(n)
+}
+
+// 从最高位开始逐位处理 n，(a, b) = (F(k), F(k+1))，每处理一位 k 翻倍，该位是 1 时再加一
+// 最后一位只计算 F(n) 本身，不计算 F(n+1)，否则 F(n) 刚好放得下时会误报溢出
+pub fn fast_doubling<T: FibonacciInt>(n: u64) -> Result<T, OverflowError> {
+    let overflow = OverflowError { n, bits: T::BITS };
+    if n == 0 {
+        return Ok(T::ZERO);
+    }
+    let (mut a, mut b) = (T::ZERO, T::ONE);
+    for bit in (1..u64::BITS - n.leading_zeros()).rev() {
+        let (c, d) = double(a, b).ok_or(overflow)?;
+        (a, b) = if n >> bit & 1 == 1 { (d, c.checked_add(d).ok_or(overflow)?) } else { (c, d) };
+    }
+    let last = if n & 1 == 1 { a.checked_mul(a).and_then(|x| x.checked_add(b.checked_mul(b)?)) } else { double_even(a, b) };
+    last.ok_or(overflow)
+}
+
+// F(2k) = F(k) * (2F(k+1) - F(k))
+fn double_even<T: FibonacciInt>(a: T, b: T) -> Option<T> {
+    a.checked_mul(b.checked_add(b)?.checked_sub(a)?)
+}
+
+// (F(k), F(k+1)) -> (F(2k), F(2k+1))
+fn double<T: FibonacciInt>(a: T, b: T) -> Option<(T, T)> {
+    let c = double_even(a, b)?;
+    let d = a.checked_mul(a)?.checked_add(b.checked_mul(b)?)?;
+    Some((c, d))
+}
+
+type Matrix<T> = [[T; 2]; 2];
+
+fn multiply<T: FibonacciInt>(x: &Matrix<T>, y: &Matrix<T>) -> Option<Matrix<T>> {
+    let mut result = [[T::ZERO; 2]; 2];
+    for i in 0..2 {
+        for j in 0..2 {
+            let first = x[i][0].checked_mul(y[0][j])?;
+            let second = x[i][1].checked_mul(y[1][j])?;
+            result[i][j] = first.checked_add(second)?;
+        }
+    }
+    Some(result)
+}
+
+// 计算 M^(n-1)，它的左上角就是 F(n)，整个矩阵里最大的数也只是 F(n)
+// 底数只平方到用得着的那一次为止，所以同样不会提前溢出
+pub fn matrix_power<T: FibonacciInt>(n: u64) -> Result<T, OverflowError> {
+    let overflow = OverflowError { n, bits: T::BITS };
+    if n == 0 {
+        return Ok(T::ZERO);
+    }
+    let mut exponent = n - 1;
+    let mut result = [[T::ONE, T::ZERO], [T::ZERO, T::ONE]];
+    let mut base = [[T::ONE, T::ONE], [T::ONE, T::ZERO]];
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = multiply(&result, &base).ok_or(overflow)?;
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = multiply(&base, &base).ok_or(overflow)?;
+        }
+    }
+    Ok(result[0][0])
+}
+
+// F(n) mod m，n 可以是任意 u64，中间结果用 u128 保存所以不会溢出
+pub fn fibonacci_mod(n: u64, m: u64) -> Result<u64, &'static str> {
+    if m == 0 {
+        return Err("Modulus must be positive!");
+    }
+    let m = m as u128;
+    let (mut a, mut b) = (0u128, 1 % m);
+    for bit in (0..u64::BITS - n.leading_zeros()).rev() {
+        let c = a * ((2 * b + m - a) % m) % m;
+        let d = (a * a % m + b * b % m) % m;
+        (a, b) = if n >> bit & 1 == 1 { (d, (c + d) % m) } else { (c, d) };
+    }
+    Ok(a as u64)
+}
+
+// 任意 n 的精确值，同样使用快速倍增，结果有 O(n) 位
+pub fn fibonacci_big(n: u64) -> BigUint {
+    let (mut a, mut b) = (BigUint::zero(), BigUint::one());
+    for bit in (0..u64::BITS - n.leading_zeros()).rev() {
+        let c = &a * &(&b + &b - &a);
+        let d = &a * &a + &b * &b;
+        if n >> bit & 1 == 1 {
+            b = &c + &d;
+            a = d;
         } else {
-            Ok(fibonacci(n-1).unwrap() + fibonacci(n-2).unwrap())
+            a = c;
+            b = d;
+        }
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 逐项相加，直到第一次溢出
+    fn sequence<T: FibonacciInt>() -> Vec<T> {
+        let mut values = vec![T::ZERO, T::ONE];
+        while let Some(next) = values[values.len() - 1].checked_add(values[values.len() - 2]) {
+            values.push(next);
+        }
+        values
+    }
+
+    fn check_boundary<T: FibonacciInt>(max_n: u64) {
+        let values = sequence::<T>();
+        assert_eq!(values.len() as u64, max_n + 1);
+        for (n, &value) in values.iter().enumerate() {
+            assert_eq!(fast_doubling::<T>(n as u64), Ok(value));
+            assert_eq!(matrix_power::<T>(n as u64), Ok(value));
+        }
+        for n in [max_n + 1, max_n + 2, max_n * 2, u64::MAX] {
+            let error = OverflowError { n, bits: T::BITS };
+            assert_eq!(fast_doubling::<T>(n), Err(error));
+            assert_eq!(matrix_power::<T>(n), Err(error));
+        }
+    }
+
+    #[test]
+    fn test_exact_overflow_boundary() {
+        check_boundary::<u32>(47);
+        check_boundary::<u64>(93);
+        check_boundary::<u128>(186);
+    }
+
+    #[test]
+    fn test_fibonacci() {
+        assert_eq!(fibonacci(13), Ok(233));
+        assert_eq!(fibonacci(93), Ok(12200160415121876738));
+        assert_eq!(fibonacci(94).unwrap_err().to_string(), "F(94) does not fit in u64");
+    }
+
+    #[test]
+    fn test_fibonacci_mod() {
+        let values = sequence::<u128>();
+        for m in [1, 2, 7, 1_000_000_007, u64::MAX] {
+            for (n, &value) in values.iter().enumerate() {
+                assert_eq!(fibonacci_mod(n as u64, m), Ok((value % m as u128) as u64));
+            }
+        }
+        // 模 10 的皮萨诺周期是 60
+        assert_eq!(fibonacci_mod(u64::MAX, 10), fibonacci_mod(u64::MAX % 60, 10));
+        assert_eq!(fibonacci_mod(10u64.pow(18), 1_000_000_007), Ok(209_783_453));
+        assert_eq!(fibonacci_mod(5, 0), Err("Modulus must be positive!"));
+    }
+
+    #[test]
+    fn test_fibonacci_big() {
+        for (n, &value) in sequence::<u128>().iter().enumerate() {
+            assert_eq!(fibonacci_big(n as u64).to_u128(), Some(value));
         }
+        let expected = "43466557686937456435688527675040625802564660517371780402481729089536555417949051890403879840079255169295922593080322634775209689623239873322471161642996440906533187938298969649928516003704476137795166849228875";
+        assert_eq!(fibonacci_big(1000).to_string(), expected);
+        // F(n) mod m 和 fibonacci_mod 一致
+        let big = fibonacci_big(20_000);
+        assert_eq!(big.to_string().len(), 4180);
+        let m = 1_000_000_007u64;
+        assert_eq!((&big % &BigUint::from(m)).to_u64(), fibonacci_mod(20_000, m).ok());
     }
 }
@@ -0,0 +1,289 @@
+// 经典递归问题汉诺塔：把 n 个盘子从 A 柱借助 B 柱移动到 C 柱
+// 这里给出三种解法，都以惰性迭代器的形式产生移动序列，并且产生的序列完全相同：
+// 1. 递归：直接按照定义拆成两个 n-1 的子问题
+// 2. 显式栈：用 SequentialStack 保存“待完成的任务”，模拟递归的调用栈，演示如何消除递归
+// 3. 二进制计数：第 m 步移动的盘子就是 m 的二进制末尾 0 的个数加一，源柱和目标柱可以直接算出来
+
+use std::{fmt, iter};
+
+use crate::linear_structure::SequentialStack;
+
+// 最多支持 64 个盘子，此时一共需要 2^64 - 1 步，刚好可以用 u64 计数
+pub const MAX_DISKS: u32 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Peg {
+    A,
+    B,
+    C,
+}
+
+impl Peg {
+    fn index(self) -> usize {
+        match self {
+            Peg::A => 0,
+            Peg::B => 1,
+            Peg::C => 2,
+        }
+    }
+}
+
+impl fmt::Display for Peg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Peg::A => "A",
+            Peg::B => "B",
+            Peg::C => "C",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// 盘子从 1 开始编号，1 号最小
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub disk: u32,
+    pub from: Peg,
+    pub to: Peg,
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "disk {}: {} -> {}", self.disk, self.from, self.to)
+    }
+}
+
+fn check_disks(n: u32) -> Result<(), &'static str> {
+    if n > MAX_DISKS { Err("Too many disks!") } else { Ok(()) }
+}
+
+// n 个盘子一共需要的步数
+pub fn move_count(n: u32) -> Result<u64, &'static str> {
+    check_disks(n)?;
+    Ok(((1u128 << n) - 1) as u64)
+}
+
+// 递归解法，子问题在被迭代到的时候才展开，所以同一时刻最多只有 n 层嵌套
+pub struct RecursiveMoves {
+    inner: Box<dyn Iterator<Item = Move>>,
+}
+
+impl Iterator for RecursiveMoves {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Move> {
+        self.inner.next()
+    }
+}
+
+fn solve_recursive(n: u32, from: Peg, to: Peg, via: Peg) -> Box<dyn Iterator<Item = Move>> {
+    if n == 0 {
+        return Box::new(iter::empty());
+    }
+    let before = iter::once_with(move || solve_recursive(n - 1, from, via, to)).flatten();
+    let after = iter::once_with(move || solve_recursive(n - 1, via, to, from)).flatten();
+    Box::new(before.chain(iter::once(Move { disk: n, from, to })).chain(after))
+}
+
+pub fn recursive_moves(n: u32) -> Result<RecursiveMoves, &'static str> {
+    check_disks(n)?;
+    Ok(RecursiveMoves { inner: solve_recursive(n, Peg::A, Peg::C, Peg::B) })
+}
+
+// 显式栈中的一帧：要么是一个还没展开的子问题，要么是一次可以直接输出的移动
+#[derive(Debug, Clone, Copy)]
+enum Frame {
+    Solve { n: u32, from: Peg, to: Peg, via: Peg },
+    Move(Move),
+}
+
+// 每展开一层，栈中最多增加两帧，所以 2 * MAX_DISKS + 1 就足够了
+const FRAME_CAPACITY: usize = 2 * MAX_DISKS as usize + 1;
+
+pub struct IterativeMoves {
+    stack: SequentialStack<Frame, FRAME_CAPACITY>,
+}
+
+impl Iterator for IterativeMoves {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Move> {
+        while let Ok(frame) = self.stack.pop() {
+            match frame {
+                Frame::Move(m) => return Some(m),
+                Frame::Solve { n: 0, .. } => {}
+                Frame::Solve { n, from, to, via } => {
+                    // 和递归的执行顺序相反地入栈：先压后做的子问题
+                    self.stack.push(Frame::Solve { n: n - 1, from: via, to, via: from }).unwrap();
+                    self.stack.push(Frame::Move(Move { disk: n, from, to })).unwrap();
+                    self.stack.push(Frame::Solve { n: n - 1, from, to: via, via: to }).unwrap();
+                }
+            }
+        }
+        None
+    }
+}
+
+pub fn iterative_moves(n: u32) -> Result<IterativeMoves, &'static str> {
+    check_disks(n)?;
+    let mut stack = SequentialStack::new();
+    stack.push(Frame::Solve { n, from: Peg::A, to: Peg::C, via: Peg::B })?;
+    Ok(IterativeMoves { stack })
+}
+
+pub struct BinaryMoves {
+    n: u32,
+    step: u128,
+    total: u128,
+}
+
+impl Iterator for BinaryMoves {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Move> {
+        if self.step > self.total {
+            return None;
+        }
+        let m = self.step;
+        self.step += 1;
+        // 按 0、1、2 编号柱子时，这个公式把盘子从 0 号柱移到 n 为奇数时的 2 号柱、n 为偶数时的 1 号柱
+        let from = ((m & (m - 1)) % 3) as usize;
+        let to = (((m | (m - 1)) + 1) % 3) as usize;
+        let pegs = if self.n % 2 == 1 { [Peg::A, Peg::B, Peg::C] } else { [Peg::A, Peg::C, Peg::B] };
+        Some(Move { disk: m.trailing_zeros() + 1, from: pegs[from], to: pegs[to] })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.total + 1 - self.step;
+        match usize::try_from(remaining) {
+            Ok(r) => (r, Some(r)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+}
+
+pub fn binary_moves(n: u32) -> Result<BinaryMoves, &'static str> {
+    check_disks(n)?;
+    Ok(BinaryMoves { n, step: 1, total: (1u128 << n) - 1 })
+}
+
+// 三根柱子的状态，每根柱子都是一个栈，用来检查移动是否合法
+pub struct Towers {
+    pegs: [SequentialStack<u32, { MAX_DISKS as usize }>; 3],
+    n: u32,
+}
+
+impl Towers {
+    // n 个盘子都在 A 柱上
+    pub fn new(n: u32) -> Result<Self, &'static str> {
+        check_disks(n)?;
+        let mut pegs = [SequentialStack::new(), SequentialStack::new(), SequentialStack::new()];
+        for disk in (1..=n).rev() {
+            pegs[0].push(disk)?;
+        }
+        Ok(Self { pegs, n })
+    }
+
+    pub fn apply(&mut self, m: Move) -> Result<(), &'static str> {
+        let top = *self.pegs[m.from.index()].get().map_err(|_| "Illegal move: source peg is empty!")?;
+        if top != m.disk {
+            return Err("Illegal move: disk is not on top of the source peg!");
+        }
+        if let Ok(&below) = self.pegs[m.to.index()].get()
+            && below < m.disk
+        {
+            return Err("Illegal move: larger disk on a smaller one!");
+        }
+        let disk = self.pegs[m.from.index()].pop()?;
+        self.pegs[m.to.index()].push(disk)
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.pegs[Peg::C.index()].len() == self.n as usize
+    }
+
+    // 某根柱子上的盘子，从底到顶
+    pub fn peg(&self, peg: Peg) -> Vec<u32> {
+        let mut disks: Vec<u32> = self.pegs[peg.index()].iter().copied().collect();
+        disks.reverse();
+        disks
+    }
+}
+
+// 依次执行所有移动并检查合法性，最后所有盘子都必须在 C 柱上，返回移动的步数
+pub fn validate<I: IntoIterator<Item = Move>>(n: u32, moves: I) -> Result<u64, &'static str> {
+    let mut towers = Towers::new(n)?;
+    let mut count = 0;
+    for m in moves {
+        towers.apply(m)?;
+        count += 1;
+    }
+    if towers.is_solved() { Ok(count) } else { Err("Puzzle is not solved!") }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_cases() {
+        assert_eq!(recursive_moves(0).unwrap().count(), 0);
+        let moves: Vec<Move> = recursive_moves(2).unwrap().collect();
+        assert_eq!(
+            moves,
+            vec![
+                Move { disk: 1, from: Peg::A, to: Peg::B },
+                Move { disk: 2, from: Peg::A, to: Peg::C },
+                Move { disk: 1, from: Peg::B, to: Peg::C },
+            ]
+        );
+        assert_eq!(moves[1].to_string(), "disk 2: A -> C");
+    }
+
+    #[test]
+    fn test_three_solvers_are_identical() {
+        for n in 0..=12 {
+            let recursive: Vec<Move> = recursive_moves(n).unwrap().collect();
+            let iterative: Vec<Move> = iterative_moves(n).unwrap().collect();
+            let binary: Vec<Move> = binary_moves(n).unwrap().collect();
+            assert_eq!(recursive.len() as u64, move_count(n).unwrap());
+            assert_eq!(recursive, iterative, "n = {}", n);
+            assert_eq!(recursive, binary, "n = {}", n);
+            assert_eq!(validate(n, recursive).unwrap(), move_count(n).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_lazy_for_many_disks() {
+        // 64 个盘子的完整序列不可能生成完，但是前面的若干步可以立刻得到
+        let n = MAX_DISKS;
+        let recursive = recursive_moves(n).unwrap().take(1000);
+        let iterative = iterative_moves(n).unwrap().take(1000);
+        let binary = binary_moves(n).unwrap().take(1000);
+        assert!(recursive.eq(iterative));
+        assert!(iterative_moves(n).unwrap().take(1000).eq(binary));
+        assert_eq!(move_count(n).unwrap(), u64::MAX);
+        assert_eq!(binary_moves(n).unwrap().size_hint().1, if usize::BITS == 64 { Some(usize::MAX) } else { None });
+    }
+
+    #[test]
+    fn test_too_many_disks() {
+        assert!(recursive_moves(MAX_DISKS + 1).is_err());
+        assert!(iterative_moves(MAX_DISKS + 1).is_err());
+        assert!(binary_moves(MAX_DISKS + 1).is_err());
+        assert_eq!(move_count(65).unwrap_err(), "Too many disks!");
+    }
+
+    #[test]
+    fn test_illegal_moves() {
+        let mut towers = Towers::new(3).unwrap();
+        assert_eq!(towers.apply(Move { disk: 1, from: Peg::B, to: Peg::C }), Err("Illegal move: source peg is empty!"));
+        assert_eq!(towers.apply(Move { disk: 2, from: Peg::A, to: Peg::C }), Err("Illegal move: disk is not on top of the source peg!"));
+        towers.apply(Move { disk: 1, from: Peg::A, to: Peg::C }).unwrap();
+        assert_eq!(towers.apply(Move { disk: 2, from: Peg::A, to: Peg::C }), Err("Illegal move: larger disk on a smaller one!"));
+        assert_eq!(towers.peg(Peg::A), vec![3, 2]);
+        assert_eq!(towers.peg(Peg::C), vec![1]);
+
+        assert_eq!(validate(2, [Move { disk: 1, from: Peg::A, to: Peg::B }]), Err("Puzzle is not solved!"));
+    }
+}
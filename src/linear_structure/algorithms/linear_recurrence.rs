@@ -0,0 +1,359 @@
+// k 阶常系数线性递推：a(n) = c[0] * a(n-1) + c[1] * a(n-2) + ... + c[k-1] * a(n-k)
+// 斐波那契、卢卡斯、Tribonacci、佩尔、Padovan 数列都是它的特例
+// 求第 n 项有两种方法：
+// 1. 矩阵快速幂：把相邻 k 项看作一个向量，每一步乘以 k × k 的伴随矩阵，O(k^3 log n)
+// 2. Kitamasa：a(n) 可以写成 a(0..k) 的线性组合，组合系数就是 x^n 除以特征多项式的余式，O(k^2 log n)
+// 系数和初始项都是非负数，所以中间结果只会增大，用 u128 计算并检查溢出，最终结果放不下 u64 时返回 OverflowError
+// 初始项中有 0 时结果可能远小于中间值，这时中间值超出 u128 也会返回 OverflowError，需要精确值请用 nth_mod 或迭代器
+
+use std::collections::VecDeque;
+
+use super::fibonacci::OverflowError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinearRecurrence {
+    coefficients: Vec<u64>,
+    initial: Vec<u64>,
+}
+
+// 中间结果的运算方式：检查溢出，或者对 m 取模
+#[derive(Clone, Copy)]
+enum Arithmetic {
+    Checked,
+    Modulo(u128),
+}
+
+impl Arithmetic {
+    fn reduce(self, x: u64) -> u128 {
+        match self {
+            Arithmetic::Checked => x as u128,
+            Arithmetic::Modulo(m) => x as u128 % m,
+        }
+    }
+
+    fn add(self, a: u128, b: u128) -> Option<u128> {
+        match self {
+            Arithmetic::Checked => a.checked_add(b),
+            Arithmetic::Modulo(m) => Some((a + b) % m),
+        }
+    }
+
+    // 取模时 a 和 b 都小于 m < 2^64，乘积不会超过 u128
+    fn mul(self, a: u128, b: u128) -> Option<u128> {
+        match self {
+            Arithmetic::Checked => a.checked_mul(b),
+            Arithmetic::Modulo(m) => Some(a * b % m),
+        }
+    }
+
+    // 转换成最终结果，检查溢出时放不下 u64 返回 None
+    fn finish(self, x: u128) -> Option<u64> {
+        match self {
+            Arithmetic::Checked => u64::try_from(x).ok(),
+            // 取模之后 x < m <= u64::MAX，一定放得下
+            Arithmetic::Modulo(_) => Some(x as u64),
+        }
+    }
+}
+
+type Matrix = Vec<Vec<u128>>;
+
+impl LinearRecurrence {
+    // coefficients 和 initial 的长度就是递推的阶数 k，必须相同并且大于 0
+    pub fn new(coefficients: Vec<u64>, initial: Vec<u64>) -> Result<Self, &'static str> {
+        if coefficients.is_empty() {
+            return Err("Recurrence must have at least one term!");
+        }
+        if coefficients.len() != initial.len() {
+            return Err("Coefficients and initial terms must have the same length!");
+        }
+        Ok(Self { coefficients, initial })
+    }
+
+    // 0, 1, 1, 2, 3, 5, 8, ...
+    pub fn fibonacci() -> Self {
+        Self { coefficients: vec![1, 1], initial: vec![0, 1] }
+    }
+
+    // 2, 1, 3, 4, 7, 11, 18, ...
+    pub fn lucas() -> Self {
+        Self { coefficients: vec![1, 1], initial: vec![2, 1] }
+    }
+
+    // 0, 0, 1, 1, 2, 4, 7, 13, ...
+    pub fn tribonacci() -> Self {
+        Self { coefficients: vec![1, 1, 1], initial: vec![0, 0, 1] }
+    }
+
+    // 0, 1, 2, 5, 12, 29, 70, ...
+    pub fn pell() -> Self {
+        Self { coefficients: vec![2, 1], initial: vec![0, 1] }
+    }
+
+    // P(n) = P(n-2) + P(n-3)：1, 1, 1, 2, 2, 3, 4, 5, 7, ...
+    pub fn padovan() -> Self {
+        Self { coefficients: vec![0, 1, 1], initial: vec![1, 1, 1] }
+    }
+
+    pub fn order(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    pub fn coefficients(&self) -> &[u64] {
+        &self.coefficients
+    }
+
+    pub fn initial(&self) -> &[u64] {
+        &self.initial
+    }
+
+    // 依次产生每一项，下一项放不下 u64 时结束
+    pub fn iter(&self) -> Terms<'_> {
+        Terms { coefficients: &self.coefficients, window: self.initial.iter().copied().collect(), overflowed: false }
+    }
+
+    // 默认使用 Kitamasa
+    pub fn nth(&self, n: u64) -> Result<u64, OverflowError> {
+        self.kitamasa(n, Arithmetic::Checked).ok_or(OverflowError { n, bits: u64::BITS })
+    }
+
+    pub fn nth_matrix(&self, n: u64) -> Result<u64, OverflowError> {
+        self.matrix_power(n, Arithmetic::Checked).ok_or(OverflowError { n, bits: u64::BITS })
+    }
+
+    // a(n) mod m，n 可以是任意 u64
+    pub fn nth_mod(&self, n: u64, m: u64) -> Result<u64, &'static str> {
+        let arithmetic = modulo(m)?;
+        Ok(self.kitamasa(n, arithmetic).expect("modular arithmetic never overflows"))
+    }
+
+    pub fn nth_matrix_mod(&self, n: u64, m: u64) -> Result<u64, &'static str> {
+        let arithmetic = modulo(m)?;
+        Ok(self.matrix_power(n, arithmetic).expect("modular arithmetic never overflows"))
+    }
+
+    // 伴随矩阵 M 把 [a(i+k-1), ..., a(i)] 变成 [a(i+k), ..., a(i+1)]
+    // 所以 a(n) 是 M^n * [a(k-1), ..., a(0)] 的最后一个分量
+    fn matrix_power(&self, n: u64, arithmetic: Arithmetic) -> Option<u64> {
+        let k = self.order();
+        if n < k as u64 {
+            return arithmetic.finish(arithmetic.reduce(self.initial[n as usize]));
+        }
+        let mut base = vec![vec![0u128; k]; k];
+        for (j, &c) in self.coefficients.iter().enumerate() {
+            base[0][j] = arithmetic.reduce(c);
+        }
+        for i in 1..k {
+            base[i][i - 1] = arithmetic.reduce(1);
+        }
+        let mut result: Matrix = (0..k).map(|i| (0..k).map(|j| arithmetic.reduce((i == j) as u64)).collect()).collect();
+        let mut exponent = n;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = multiply_matrix(&result, &base, arithmetic)?;
+            }
+            exponent >>= 1;
+            // 只平方到用得着的那一次为止，否则会算出远大于结果的中间值
+            if exponent > 0 {
+                base = multiply_matrix(&base, &base, arithmetic)?;
+            }
+        }
+        let mut term = 0;
+        for (j, &entry) in result[k - 1].iter().enumerate() {
+            let product = arithmetic.mul(entry, arithmetic.reduce(self.initial[k - 1 - j]))?;
+            term = arithmetic.add(term, product)?;
+        }
+        arithmetic.finish(term)
+    }
+
+    // 用特征多项式 x^k = c[0] x^(k-1) + ... + c[k-1] 不断降次，求出 x^n 的余式 r(x)
+    // 那么 a(n) = r[0] * a(0) + r[1] * a(1) + ... + r[k-1] * a(k-1)
+    fn kitamasa(&self, n: u64, arithmetic: Arithmetic) -> Option<u64> {
+        let k = self.order();
+        if n < k as u64 {
+            return arithmetic.finish(arithmetic.reduce(self.initial[n as usize]));
+        }
+        // x 本身的余式，k = 1 时 x = c[0]
+        let mut base = vec![0u128; k];
+        if k == 1 {
+            base[0] = arithmetic.reduce(self.coefficients[0]);
+        } else {
+            base[1] = arithmetic.reduce(1);
+        }
+        let mut result = vec![0u128; k];
+        result[0] = arithmetic.reduce(1);
+        let mut exponent = n;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = self.multiply_polynomial(&result, &base, arithmetic)?;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = self.multiply_polynomial(&base, &base, arithmetic)?;
+            }
+        }
+        let mut term = 0;
+        for (&r, &a) in result.iter().zip(&self.initial) {
+            term = arithmetic.add(term, arithmetic.mul(r, arithmetic.reduce(a))?)?;
+        }
+        arithmetic.finish(term)
+    }
+
+    // 两个余式相乘之后再对特征多项式取余，从最高次开始把 x^d 换成 x^(d-k) * (c[0] x^(k-1) + ...)
+    fn multiply_polynomial(&self, p: &[u128], q: &[u128], arithmetic: Arithmetic) -> Option<Vec<u128>> {
+        let k = self.order();
+        let mut product = vec![0u128; 2 * k - 1];
+        for (i, &x) in p.iter().enumerate() {
+            for (j, &y) in q.iter().enumerate() {
+                product[i + j] = arithmetic.add(product[i + j], arithmetic.mul(x, y)?)?;
+            }
+        }
+        for d in (k..2 * k - 1).rev() {
+            let value = product[d];
+            for (j, &c) in self.coefficients.iter().enumerate() {
+                let term = arithmetic.mul(value, arithmetic.reduce(c))?;
+                product[d - 1 - j] = arithmetic.add(product[d - 1 - j], term)?;
+            }
+        }
+        product.truncate(k);
+        Some(product)
+    }
+}
+
+fn modulo(m: u64) -> Result<Arithmetic, &'static str> {
+    if m == 0 { Err("Modulus must be positive!") } else { Ok(Arithmetic::Modulo(m as u128)) }
+}
+
+fn multiply_matrix(x: &Matrix, y: &Matrix, arithmetic: Arithmetic) -> Option<Matrix> {
+    let k = x.len();
+    let mut result = vec![vec![0u128; k]; k];
+    for i in 0..k {
+        for l in 0..k {
+            if x[i][l] == 0 {
+                continue;
+            }
+            for j in 0..k {
+                result[i][j] = arithmetic.add(result[i][j], arithmetic.mul(x[i][l], y[l][j])?)?;
+            }
+        }
+    }
+    Some(result)
+}
+
+// window 保存接下来的 k 项，每取出一项就在末尾补上新的一项
+pub struct Terms<'a> {
+    coefficients: &'a [u64],
+    window: VecDeque<u64>,
+    overflowed: bool,
+}
+
+impl Iterator for Terms<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if !self.overflowed {
+            let next = self.coefficients.iter().zip(self.window.iter().rev()).try_fold(0u64, |acc, (&c, &a)| acc.checked_add(c.checked_mul(a)?));
+            match next {
+                Some(next) => self.window.push_back(next),
+                // 窗口里剩下的项仍然是正确的，全部取完之后结束
+                None => self.overflowed = true,
+            }
+        }
+        self.window.pop_front()
+    }
+}
+
+impl<'a> IntoIterator for &'a LinearRecurrence {
+    type Item = u64;
+    type IntoIter = Terms<'a>;
+
+    fn into_iter(self) -> Terms<'a> {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linear_structure::algorithms::fibonacci::{fibonacci, fibonacci_mod};
+    use crate::linear_structure::test_rng::TestRng;
+
+    fn presets() -> Vec<LinearRecurrence> {
+        vec![
+            LinearRecurrence::fibonacci(),
+            LinearRecurrence::lucas(),
+            LinearRecurrence::tribonacci(),
+            LinearRecurrence::pell(),
+            LinearRecurrence::padovan(),
+        ]
+    }
+
+    #[test]
+    fn test_presets() {
+        let first = |r: LinearRecurrence| r.iter().take(12).collect::<Vec<_>>();
+        assert_eq!(first(LinearRecurrence::fibonacci()), [0, 1, 1, 2, 3, 5, 8, 13, 21, 34, 55, 89]);
+        assert_eq!(first(LinearRecurrence::lucas()), [2, 1, 3, 4, 7, 11, 18, 29, 47, 76, 123, 199]);
+        assert_eq!(first(LinearRecurrence::tribonacci()), [0, 0, 1, 1, 2, 4, 7, 13, 24, 44, 81, 149]);
+        assert_eq!(first(LinearRecurrence::pell()), [0, 1, 2, 5, 12, 29, 70, 169, 408, 985, 2378, 5741]);
+        assert_eq!(first(LinearRecurrence::padovan()), [1, 1, 1, 2, 2, 3, 4, 5, 7, 9, 12, 16]);
+        assert_eq!(LinearRecurrence::fibonacci().iter().count(), 94);
+    }
+
+    #[test]
+    fn test_nth_matches_iterator() {
+        for recurrence in presets() {
+            let terms: Vec<u64> = recurrence.iter().collect();
+            for (n, &term) in terms.iter().enumerate() {
+                assert_eq!(recurrence.nth(n as u64), Ok(term));
+                assert_eq!(recurrence.nth_matrix(n as u64), Ok(term));
+            }
+            // 迭代器在第一个溢出的项处结束，nth 也恰好从这一项开始报告溢出
+            let n = terms.len() as u64;
+            assert_eq!(recurrence.nth(n), Err(OverflowError { n, bits: 64 }));
+            assert_eq!(recurrence.nth_matrix(n), Err(OverflowError { n, bits: 64 }));
+        }
+        assert_eq!(LinearRecurrence::fibonacci().nth(93), fibonacci(93));
+    }
+
+    #[test]
+    fn test_modular() {
+        let fib = LinearRecurrence::fibonacci();
+        for n in [0, 1, 2, 100, 12345, 10u64.pow(18), u64::MAX] {
+            assert_eq!(fib.nth_mod(n, 1_000_000_007).ok(), fibonacci_mod(n, 1_000_000_007).ok());
+            assert_eq!(fib.nth_matrix_mod(n, 998_244_353).ok(), fibonacci_mod(n, 998_244_353).ok());
+        }
+        for recurrence in presets() {
+            for (n, term) in recurrence.iter().enumerate() {
+                assert_eq!(recurrence.nth_mod(n as u64, 1000), Ok(term % 1000));
+                assert_eq!(recurrence.nth_matrix_mod(n as u64, u64::MAX), Ok(term % u64::MAX));
+            }
+            assert_eq!(recurrence.nth_mod(5, 1), Ok(0));
+            assert_eq!(recurrence.nth_mod(5, 0), Err("Modulus must be positive!"));
+        }
+    }
+
+    #[test]
+    fn test_general_recurrences() {
+        // 随机系数的 5 阶递推，两种方法结果相同
+        let mut rng = TestRng::new(2024);
+        let mut next = || (rng.next_u32() >> 16) as u64;
+        for _ in 0..20 {
+            let recurrence = LinearRecurrence::new((0..5).map(|_| next() % 4).collect(), (0..5).map(|_| next()).collect()).unwrap();
+            let n = next() * next();
+            assert_eq!(recurrence.nth_mod(n, 1_000_003), recurrence.nth_matrix_mod(n, 1_000_003));
+        }
+
+        // 1 阶递推就是等比数列
+        let powers = LinearRecurrence::new(vec![3], vec![1]).unwrap();
+        assert_eq!(powers.nth(40), Ok(3u64.pow(40)));
+        assert_eq!(powers.nth_matrix(40), Ok(3u64.pow(40)));
+        assert_eq!(powers.iter().count(), 41);
+
+        // 全是 0 的初始项，矩阵的幂虽然很大，但每一项都是 0
+        let zeros = LinearRecurrence::new(vec![1, 1], vec![0, 0]).unwrap();
+        assert_eq!(zeros.nth(50), Ok(0));
+
+        assert_eq!(LinearRecurrence::new(vec![], vec![]), Err("Recurrence must have at least one term!"));
+        assert_eq!(LinearRecurrence::new(vec![1], vec![1, 2]), Err("Coefficients and initial terms must have the same length!"));
+    }
+}
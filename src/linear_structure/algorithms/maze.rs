@@ -0,0 +1,380 @@
+// 栈的经典应用：迷宫求解
+// 迷宫用文本表示，# 是墙，S 是起点，E 是出口，空格或者 . 是可以走的格子
+// 使用链栈做迭代的深度优先搜索，并通过记录每个格子的前驱来还原路径
+
+use std::{fmt, fs, io, path::Path, str::FromStr};
+
+use crate::linear_structure::LinkedStack;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Wall,
+    Open,
+    Start,
+    Exit,
+}
+
+// 行列都从 0 开始
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub row: usize,
+    pub col: usize,
+}
+
+#[derive(Debug)]
+pub enum MazeError {
+    Io(io::Error),
+    // 行列从 1 开始，方便和编辑器对照
+    InvalidCell { line: usize, column: usize, found: char },
+    DuplicateStart { line: usize, column: usize },
+    DuplicateExit { line: usize, column: usize },
+    MissingStart,
+    MissingExit,
+}
+
+impl fmt::Display for MazeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MazeError::Io(e) => write!(f, "cannot read maze: {}", e),
+            MazeError::InvalidCell { line, column, found } => write!(f, "{}:{}: invalid cell {:?}", line, column, found),
+            MazeError::DuplicateStart { line, column } => write!(f, "{}:{}: more than one start 'S'", line, column),
+            MazeError::DuplicateExit { line, column } => write!(f, "{}:{}: more than one exit 'E'", line, column),
+            MazeError::MissingStart => write!(f, "maze has no start 'S'"),
+            MazeError::MissingExit => write!(f, "maze has no exit 'E'"),
+        }
+    }
+}
+
+impl std::error::Error for MazeError {}
+
+impl From<io::Error> for MazeError {
+    fn from(e: io::Error) -> Self {
+        MazeError::Io(e)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Maze {
+    cells: Vec<Vec<Cell>>,
+    width: usize,
+    start: Position,
+    exit: Position,
+}
+
+// 搜索的方向顺序：上、右、下、左
+const DIRECTIONS: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+impl FromStr for Maze {
+    type Err = MazeError;
+
+    fn from_str(text: &str) -> Result<Self, MazeError> {
+        let mut cells = Vec::new();
+        let mut start = None;
+        let mut exit = None;
+        for (row, line) in text.lines().enumerate() {
+            let mut cells_of_row = Vec::new();
+            for (col, c) in line.chars().enumerate() {
+                let (line, column) = (row + 1, col + 1);
+                let cell = match c {
+                    '#' => Cell::Wall,
+                    ' ' | '.' => Cell::Open,
+                    'S' if start.is_some() => return Err(MazeError::DuplicateStart { line, column }),
+                    'S' => {
+                        start = Some(Position { row, col });
+                        Cell::Start
+                    }
+                    'E' if exit.is_some() => return Err(MazeError::DuplicateExit { line, column }),
+                    'E' => {
+                        exit = Some(Position { row, col });
+                        Cell::Exit
+                    }
+                    _ => return Err(MazeError::InvalidCell { line, column, found: c }),
+                };
+                cells_of_row.push(cell);
+            }
+            cells.push(cells_of_row);
+        }
+        let width = cells.iter().map(Vec::len).max().unwrap_or(0);
+        Ok(Self {
+            cells,
+            width,
+            start: start.ok_or(MazeError::MissingStart)?,
+            exit: exit.ok_or(MazeError::MissingExit)?,
+        })
+    }
+}
+
+impl Maze {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, MazeError> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    pub fn start(&self) -> Position {
+        self.start
+    }
+
+    pub fn exit(&self) -> Position {
+        self.exit
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    // 每一行的长度可以不同，超出行尾的位置当作墙
+    pub fn cell(&self, pos: Position) -> Cell {
+        self.cells.get(pos.row).and_then(|r| r.get(pos.col)).copied().unwrap_or(Cell::Wall)
+    }
+
+    fn index(&self, pos: Position) -> usize {
+        pos.row * self.width + pos.col
+    }
+
+    fn neighbor(&self, pos: Position, direction: usize) -> Option<Position> {
+        let (dr, dc) = DIRECTIONS[direction];
+        let row = pos.row.checked_add_signed(dr)?;
+        let col = pos.col.checked_add_signed(dc)?;
+        let next = Position { row, col };
+        (self.cell(next) != Cell::Wall).then_some(next)
+    }
+
+    // 沿着前驱从出口倒推回起点
+    fn reconstruct(&self, parents: &[Option<Position>]) -> Vec<Position> {
+        let mut path = vec![self.exit];
+        let mut current = self.exit;
+        while let Some(parent) = parents[self.index(current)] {
+            path.push(parent);
+            current = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    // 迭代的深度优先搜索，返回从起点到出口的一条路径（包含起点和出口），找不到时返回 None
+    pub fn solve(&self) -> Option<Vec<Position>> {
+        let size = self.height() * self.width;
+        let mut visited = vec![false; size];
+        let mut parents: Vec<Option<Position>> = vec![None; size];
+        let mut stack: LinkedStack<Position> = LinkedStack::new();
+
+        visited[self.index(self.start)] = true;
+        stack.push(self.start);
+        while let Ok(pos) = stack.pop() {
+            if pos == self.exit {
+                return Some(self.reconstruct(&parents));
+            }
+            // 逆序入栈，这样出栈时按 上、右、下、左 的顺序探索
+            for direction in (0..DIRECTIONS.len()).rev() {
+                if let Some(next) = self.neighbor(pos, direction) {
+                    let index = self.index(next);
+                    if !visited[index] {
+                        visited[index] = true;
+                        parents[index] = Some(pos);
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // 枚举从起点到出口的所有简单路径（不重复经过同一个格子），路径是惰性生成的
+    pub fn all_paths(&self) -> AllPaths<'_> {
+        let size = self.height() * self.width;
+        let mut on_path = vec![false; size];
+        let mut stack = LinkedStack::new();
+        on_path[self.index(self.start)] = true;
+        stack.push(Frame { pos: self.start, next_direction: 0 });
+        AllPaths { maze: self, stack, on_path, parents: vec![None; size] }
+    }
+
+    // 把路径画回文本，路径上除起点和出口之外的格子用 * 标记
+    pub fn render(&self, path: &[Position]) -> String {
+        let mut grid: Vec<Vec<char>> = self
+            .cells
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| match cell {
+                        Cell::Wall => '#',
+                        Cell::Open => ' ',
+                        Cell::Start => 'S',
+                        Cell::Exit => 'E',
+                    })
+                    .collect()
+            })
+            .collect();
+        for pos in path {
+            if self.cell(*pos) == Cell::Open {
+                grid[pos.row][pos.col] = '*';
+            }
+        }
+        grid.into_iter().map(|row| row.into_iter().collect::<String>()).collect::<Vec<_>>().join("\n")
+    }
+}
+
+impl fmt::Display for Maze {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(&[]))
+    }
+}
+
+// 深度优先搜索的一帧：当前格子以及下一个要尝试的方向
+struct Frame {
+    pos: Position,
+    next_direction: usize,
+}
+
+pub struct AllPaths<'a> {
+    maze: &'a Maze,
+    stack: LinkedStack<Frame>,
+    // 当前路径上的格子，保证路径是简单路径
+    on_path: Vec<bool>,
+    parents: Vec<Option<Position>>,
+}
+
+impl Iterator for AllPaths<'_> {
+    type Item = Vec<Position>;
+
+    fn next(&mut self) -> Option<Vec<Position>> {
+        loop {
+            let frame = self.stack.peek_mut().ok()?;
+            if frame.next_direction == DIRECTIONS.len() {
+                // 所有方向都试过了，回溯
+                let pos = frame.pos;
+                self.on_path[self.maze.index(pos)] = false;
+                let _ = self.stack.pop();
+                continue;
+            }
+            let pos = frame.pos;
+            let direction = frame.next_direction;
+            frame.next_direction += 1;
+
+            let Some(next) = self.maze.neighbor(pos, direction) else { continue };
+            let index = self.maze.index(next);
+            if self.on_path[index] {
+                continue;
+            }
+            self.parents[index] = Some(pos);
+            if next == self.maze.exit {
+                return Some(self.maze.reconstruct(&self.parents));
+            }
+            self.on_path[index] = true;
+            self.stack.push(Frame { pos: next, next_direction: 0 });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIMPLE: &str = "\
+#########
+#S  #   #
+# # # # #
+# #   #E#
+#########";
+
+    fn is_valid_path(maze: &Maze, path: &[Position]) -> bool {
+        let adjacent = path.windows(2).all(|w| w[0].row.abs_diff(w[1].row) + w[0].col.abs_diff(w[1].col) == 1);
+        let mut seen = std::collections::HashSet::new();
+        let simple = path.iter().all(|p| seen.insert(*p));
+        let open = path.iter().all(|p| maze.cell(*p) != Cell::Wall);
+        path.first() == Some(&maze.start()) && path.last() == Some(&maze.exit()) && adjacent && simple && open
+    }
+
+    #[test]
+    fn test_parse() {
+        let maze: Maze = SIMPLE.parse().unwrap();
+        assert_eq!(maze.height(), 5);
+        assert_eq!(maze.width(), 9);
+        assert_eq!(maze.start(), Position { row: 1, col: 1 });
+        assert_eq!(maze.exit(), Position { row: 3, col: 7 });
+        assert_eq!(maze.to_string(), SIMPLE);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!("#E#".parse::<Maze>(), Err(MazeError::MissingStart)));
+        assert!(matches!("#S#".parse::<Maze>(), Err(MazeError::MissingExit)));
+        assert!(matches!("S\nSE".parse::<Maze>(), Err(MazeError::DuplicateStart { line: 2, column: 1 })));
+        assert!(matches!("SEE".parse::<Maze>(), Err(MazeError::DuplicateExit { line: 1, column: 3 })));
+        let err = "S#\n#x E".parse::<Maze>().unwrap_err();
+        assert!(matches!(err, MazeError::InvalidCell { line: 2, column: 2, found: 'x' }));
+        assert_eq!(err.to_string(), "2:2: invalid cell 'x'");
+    }
+
+    #[test]
+    fn test_solve_and_render() {
+        let maze: Maze = SIMPLE.parse().unwrap();
+        let path = maze.solve().unwrap();
+        assert!(is_valid_path(&maze, &path));
+        assert_eq!(
+            maze.render(&path),
+            "\
+#########
+#S**#***#
+# #*#*#*#
+# #***#E#
+#########"
+        );
+    }
+
+    #[test]
+    fn test_unsolvable() {
+        let maze: Maze = "#####\n#S#E#\n#####".parse().unwrap();
+        assert!(maze.solve().is_none());
+        assert_eq!(maze.all_paths().count(), 0);
+    }
+
+    #[test]
+    fn test_all_paths() {
+        // 中间有一个环，左右两条路可以到达出口
+        let maze: Maze = "\
+#######
+#S    #
+# ### #
+#     #
+###E###"
+            .parse()
+            .unwrap();
+        let paths: Vec<_> = maze.all_paths().collect();
+        assert_eq!(paths.len(), 2);
+        for path in &paths {
+            assert!(is_valid_path(&maze, path));
+        }
+        assert_ne!(paths[0], paths[1]);
+
+        // 2x3 的开放区域中从左上角到右下角的简单路径一共有 4 条
+        let open: Maze = "S..\n..E".parse().unwrap();
+        let paths: Vec<_> = open.all_paths().collect();
+        assert_eq!(paths.len(), 4);
+        assert!(paths.iter().all(|p| is_valid_path(&open, p)));
+    }
+
+    #[test]
+    fn test_ragged_lines_and_large_maze() {
+        let maze: Maze = "S  \n #\n E".parse().unwrap();
+        assert!(is_valid_path(&maze, &maze.solve().unwrap()));
+
+        // 很长的走廊，深度优先搜索不会因为递归过深而栈溢出
+        let corridor = format!("S{}E", " ".repeat(100_000));
+        let maze: Maze = corridor.parse().unwrap();
+        assert_eq!(maze.solve().unwrap().len(), 100_002);
+    }
+
+    #[test]
+    fn test_from_file() {
+        let path = std::env::temp_dir().join(format!("maze_test_{}.txt", std::process::id()));
+        fs::write(&path, SIMPLE).unwrap();
+        let maze = Maze::from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(maze.solve().is_some());
+        assert!(matches!(Maze::from_file(&path), Err(MazeError::Io(_))));
+    }
+}
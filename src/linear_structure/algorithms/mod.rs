@@ -1,2 +1,10 @@
+pub mod big_uint;
+pub mod brackets;
+pub mod call_tree;
+pub mod expression;
 pub mod fibonacci;
+pub mod hanoi;
+pub mod linear_recurrence;
+pub mod maze;
+pub mod recursion;
 pub use fibonacci::fibonacci;
\ No newline at end of file
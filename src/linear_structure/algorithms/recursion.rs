@@ -0,0 +1,283 @@
+// 递归的两类常见问题以及对应的工具：
+// 1. 重复计算：朴素递归的斐波那契会把同一个子问题算很多次。Memo 缓存每个参数的结果（自顶向下），
+//    bottom_up 则按从小到大的顺序填表（自底向上），两种方式都只计算每个子问题一次
+// 2. 调用栈溢出：递归深度达到 10^6 时线程栈就不够用了。trampoline 把尾递归变成循环，
+//    run_iterative 用 LinkedStack 保存“还没算完的调用”，模拟调用栈，深度只受堆内存限制
+
+use std::{collections::HashMap, hash::Hash, rc::Rc};
+
+use crate::linear_structure::LinkedStack;
+
+// 显式栈的结点池容量，递归展开和返回交替进行时可以复用结点
+const FRAME_POOL_CAPACITY: usize = 64;
+
+type Recursive<K, V> = Rc<dyn Fn(&mut Memo<K, V>, K) -> V>;
+
+// 带缓存的递归函数，函数体通过第一个参数 memo.get(...) 发起递归调用
+// 注意递归仍然发生在线程栈上，递归很深时请使用 run_iterative_memo
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    function: Recursive<K, V>,
+    hits: usize,
+    misses: usize,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Memo<K, V> {
+    pub fn new(function: impl Fn(&mut Memo<K, V>, K) -> V + 'static) -> Self {
+        Self { cache: HashMap::new(), function: Rc::new(function), hits: 0, misses: 0 }
+    }
+
+    pub fn get(&mut self, key: K) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.hits += 1;
+            return value.clone();
+        }
+        self.misses += 1;
+        let function = Rc::clone(&self.function);
+        let value = function(self, key.clone());
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    // 已经缓存的结果个数
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    // 命中缓存的次数
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    // 真正执行函数体的次数
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+}
+
+// 自顶向下：从要求的参数出发递归，子问题的结果都会被缓存
+pub fn top_down<K: Eq + Hash + Clone, V: Clone>(key: K, function: impl Fn(&mut Memo<K, V>, K) -> V + 'static) -> V {
+    Memo::new(function).get(key)
+}
+
+// 自底向上：依次计算 table[0], table[1], ..., table[n]，计算第 i 项时前面的 i 项都已经算好
+pub fn bottom_up<V>(n: usize, mut function: impl FnMut(&[V], usize) -> V) -> Vec<V> {
+    let mut table = Vec::with_capacity(n + 1);
+    for i in 0..=n {
+        let value = function(&table, i);
+        table.push(value);
+    }
+    table
+}
+
+// 尾递归的一步：要么已经得到结果，要么用新的参数再调用一次自己
+pub enum Bounce<A, V> {
+    Done(V),
+    Continue(A),
+}
+
+// 尾递归不需要保存任何东西，直接变成循环
+pub fn trampoline<A, V>(arg: A, mut function: impl FnMut(A) -> Bounce<A, V>) -> V {
+    let mut arg = arg;
+    loop {
+        match function(arg) {
+            Bounce::Done(value) => return value,
+            Bounce::Continue(next) => arg = next,
+        }
+    }
+}
+
+// 一般递归的一步：要么直接返回结果，要么依次递归计算若干个子问题，再用 combine 把它们的结果合并起来
+pub enum Step<'a, A, V> {
+    Return(V),
+    Recurse(Vec<A>, Box<dyn FnOnce(Vec<V>) -> V + 'a>),
+}
+
+impl<'a, A, V> Step<'a, A, V> {
+    pub fn recurse(args: Vec<A>, combine: impl FnOnce(Vec<V>) -> V + 'a) -> Self {
+        Step::Recurse(args, Box::new(combine))
+    }
+}
+
+// 用显式栈执行递归，step 描述一次调用做什么
+pub fn run_iterative<'a, A, V>(arg: A, step: impl FnMut(A) -> Step<'a, A, V>) -> V {
+    drive(arg, step, &mut ())
+}
+
+// 同上，并且缓存每个参数的结果，相同的子问题只展开一次
+pub fn run_iterative_memo<'a, A: Eq + Hash + Clone, V: Clone>(arg: A, step: impl FnMut(A) -> Step<'a, A, V>) -> V {
+    drive(arg, step, &mut HashMap::new())
+}
+
+// 结果缓存，() 表示不缓存
+trait ResultCache<A, V> {
+    type Key;
+
+    // 没有命中时返回之后保存结果要用的键
+    fn lookup(&mut self, arg: &A) -> Result<V, Self::Key>;
+    fn store(&mut self, key: Self::Key, value: &V);
+}
+
+impl<A, V> ResultCache<A, V> for () {
+    type Key = ();
+
+    fn lookup(&mut self, _arg: &A) -> Result<V, ()> {
+        Err(())
+    }
+
+    fn store(&mut self, _key: (), _value: &V) {}
+}
+
+impl<A: Eq + Hash + Clone, V: Clone> ResultCache<A, V> for HashMap<A, V> {
+    type Key = A;
+
+    fn lookup(&mut self, arg: &A) -> Result<V, A> {
+        self.get(arg).cloned().ok_or_else(|| arg.clone())
+    }
+
+    fn store(&mut self, key: A, value: &V) {
+        self.insert(key, value.clone());
+    }
+}
+
+// 一次还没有返回的调用：还没展开的子问题（倒序保存，方便从末尾取出）、已经得到的子问题结果和合并函数
+struct Frame<'a, A, V, K> {
+    key: K,
+    pending: Vec<A>,
+    results: Vec<V>,
+    combine: Box<dyn FnOnce(Vec<V>) -> V + 'a>,
+}
+
+fn drive<'a, A, V, C: ResultCache<A, V>>(arg: A, mut step: impl FnMut(A) -> Step<'a, A, V>, cache: &mut C) -> V {
+    let mut stack: LinkedStack<Frame<'a, A, V, C::Key>> = LinkedStack::with_pool_capacity(FRAME_POOL_CAPACITY);
+    let mut arg = arg;
+    loop {
+        // 展开一个调用：要么直接得到结果，要么压入一帧
+        let mut value = match cache.lookup(&arg) {
+            Ok(value) => Some(value),
+            Err(key) => match step(arg) {
+                Step::Return(value) => {
+                    cache.store(key, &value);
+                    Some(value)
+                }
+                Step::Recurse(mut args, combine) => {
+                    args.reverse();
+                    stack.push(Frame { key, pending: args, results: Vec::new(), combine });
+                    None
+                }
+            },
+        };
+        // 把结果交给上一层，直到某一层还有没展开的子问题
+        loop {
+            let Ok(frame) = stack.peek_mut() else {
+                return value.expect("an empty stack always follows a returned value");
+            };
+            if let Some(value) = value.take() {
+                frame.results.push(value);
+            }
+            if let Some(next) = frame.pending.pop() {
+                arg = next;
+                break;
+            }
+            let frame = stack.pop().expect("frame was just peeked");
+            let result = (frame.combine)(frame.results);
+            cache.store(frame.key, &result);
+            value = Some(result);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linear_structure::algorithms::fibonacci::{fibonacci, fibonacci_mod};
+
+    const DEEP: u64 = 1_000_000;
+
+    #[test]
+    fn test_memo() {
+        let mut fib = Memo::new(|memo: &mut Memo<u64, u64>, n| if n < 2 { n } else { memo.get(n - 1) + memo.get(n - 2) });
+        assert_eq!(fib.get(90), fibonacci(90).unwrap());
+        // 每个参数只计算一次
+        assert_eq!(fib.misses(), 91);
+        assert_eq!(fib.len(), 91);
+        assert_eq!(fib.hits(), 88);
+        assert_eq!(fib.get(50), fibonacci(50).unwrap());
+        assert_eq!(fib.hits(), 89);
+        fib.clear();
+        assert!(fib.is_empty());
+
+        // 网格路径数：C(2n, n)
+        let paths = top_down((16u32, 16u32), |memo: &mut Memo<(u32, u32), u64>, (r, c)| {
+            if r == 0 || c == 0 { 1 } else { memo.get((r - 1, c)) + memo.get((r, c - 1)) }
+        });
+        assert_eq!(paths, 601_080_390);
+    }
+
+    #[test]
+    fn test_bottom_up() {
+        let table = bottom_up(93, |table: &[u64], i| if i < 2 { i as u64 } else { table[i - 1] + table[i - 2] });
+        assert_eq!(table.len(), 94);
+        assert_eq!(table[93], fibonacci(93).unwrap());
+        assert_eq!(bottom_up(0, |_: &[u8], _| 7), vec![7]);
+    }
+
+    #[test]
+    fn test_trampoline() {
+        let gcd = trampoline((1071u64, 462u64), |(a, b)| if b == 0 { Bounce::Done(a) } else { Bounce::Continue((b, a % b)) });
+        assert_eq!(gcd, 21);
+        let sum = trampoline((DEEP, 0u64), |(n, acc)| if n == 0 { Bounce::Done(acc) } else { Bounce::Continue((n - 1, acc + n)) });
+        assert_eq!(sum, DEEP * (DEEP + 1) / 2);
+    }
+
+    #[test]
+    fn test_run_iterative_deep() {
+        // sum(n) = n + sum(n - 1)，递归深度 10^6，直接递归会让测试线程栈溢出
+        let sum = run_iterative(DEEP, |n| {
+            if n == 0 { Step::Return(0u64) } else { Step::recurse(vec![n - 1], move |results| n + results[0]) }
+        });
+        assert_eq!(sum, DEEP * (DEEP + 1) / 2);
+
+        // 深度 10^6 并且有重复子问题，缓存之后每个参数只展开一次
+        let m = 1_000_000_007;
+        let fib = run_iterative_memo(DEEP, |n| {
+            if n < 2 { Step::Return(n) } else { Step::recurse(vec![n - 1, n - 2], move |r| (r[0] + r[1]) % m) }
+        });
+        assert_eq!(Ok(fib), fibonacci_mod(DEEP, m));
+    }
+
+    #[test]
+    fn test_run_iterative_branches() {
+        // 不缓存时和朴素递归一样，调用次数是指数级的
+        let mut calls = 0;
+        let fib = run_iterative(20u64, |n| {
+            calls += 1;
+            if n < 2 { Step::Return(n) } else { Step::recurse(vec![n - 1, n - 2], |r| r[0] + r[1]) }
+        });
+        assert_eq!(fib, 6765);
+        assert_eq!(calls, 21891);
+
+        // 子问题的结果按参数的顺序交给 combine：汉诺塔的移动序列
+        let moves = run_iterative((3u32, 'A', 'C', 'B'), |(n, from, to, via)| {
+            if n == 0 {
+                Step::Return(String::new())
+            } else {
+                Step::recurse(vec![(n - 1, from, via, to), (n - 1, via, to, from)], move |r: Vec<String>| format!("{}{}{} {}", r[0], from, to, r[1]))
+            }
+        });
+        assert_eq!(moves.split_whitespace().collect::<Vec<_>>(), ["AC", "AB", "CB", "AC", "BA", "BC", "AC"]);
+
+        // 没有子问题的 Recurse 直接调用 combine
+        assert_eq!(run_iterative((), |_| Step::recurse(vec![], |r: Vec<i32>| r.len() as i32 + 5)), 5);
+    }
+}
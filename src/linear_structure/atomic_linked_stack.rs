@@ -0,0 +1,354 @@
+// 无锁并发链栈（Treiber 栈），push 和 pop 都是对栈顶指针的 CAS 循环
+// 难点在于结点的回收：一个线程 pop 掉结点之后，别的线程可能刚刚读到这个结点的地址，还要读取它的 next，
+// 所以不能立即释放。这里用危险指针（hazard pointer）解决：
+// 1. 线程在访问结点之前，先把结点地址写进自己占用的危险指针记录，再确认栈顶没有变化
+// 2. 被 pop 的结点不立即释放，而是放进待回收链表，积累到一定数量后扫描所有危险指针，只释放没有被任何线程保护的结点
+
+use std::{
+    mem::ManuallyDrop,
+    ptr,
+    sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering},
+};
+
+struct Node<T> {
+    // pop 时数据被移出，释放结点时不能再 drop 一次
+    data: ManuallyDrop<T>,
+    // 入栈之前写好，之后不再修改，所以其他线程可以直接读
+    next: *mut Node<T>,
+    // 进入待回收链表之后使用，和 next 分开，避免修改别的线程可能正在读的字段
+    retired_next: *mut Node<T>,
+}
+
+// 危险指针记录，只会被加入链表，直到栈被 drop 才释放
+struct HazardRecord {
+    hazard: AtomicPtr<u8>,
+    active: AtomicBool,
+    next: *mut HazardRecord,
+}
+
+// 待回收的结点超过这个数量加上危险指针记录数的两倍时，扫描一次
+const RECLAIM_THRESHOLD: usize = 64;
+
+pub struct AtomicLinkedStack<T> {
+    head: AtomicPtr<Node<T>>,
+    hazards: AtomicPtr<HazardRecord>,
+    hazard_count: AtomicUsize,
+    retired: AtomicPtr<Node<T>>,
+    retired_count: AtomicUsize,
+}
+
+// 元素会在线程之间转移，所以只要求 T: Send
+unsafe impl<T: Send> Send for AtomicLinkedStack<T> {}
+unsafe impl<T: Send> Sync for AtomicLinkedStack<T> {}
+
+impl<T> Default for AtomicLinkedStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> AtomicLinkedStack<T> {
+    pub const fn new() -> Self {
+        Self {
+            head: AtomicPtr::new(ptr::null_mut()),
+            hazards: AtomicPtr::new(ptr::null_mut()),
+            hazard_count: AtomicUsize::new(0),
+            retired: AtomicPtr::new(ptr::null_mut()),
+            retired_count: AtomicUsize::new(0),
+        }
+    }
+
+    // 其他线程可能同时在修改，返回值只反映调用时的状态
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::SeqCst).is_null()
+    }
+
+    pub fn push(&self, element: T) {
+        let node = Box::into_raw(Box::new(Node {
+            data: ManuallyDrop::new(element),
+            next: ptr::null_mut(),
+            retired_next: ptr::null_mut(),
+        }));
+        let mut head = self.head.load(Ordering::SeqCst);
+        loop {
+            // 结点还没有发布，可以随意修改
+            unsafe { (*node).next = head };
+            match self.head.compare_exchange_weak(head, node, Ordering::SeqCst, Ordering::SeqCst) {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let record = self.acquire_record();
+        let result = loop {
+            let head = self.head.load(Ordering::SeqCst);
+            if head.is_null() {
+                break None;
+            }
+            // 先声明要访问 head，再确认它仍然是栈顶；确认之后它就不会被释放
+            record.hazard.store(head.cast(), Ordering::SeqCst);
+            if self.head.load(Ordering::SeqCst) != head {
+                continue;
+            }
+            let next = unsafe { (*head).next };
+            if self.head.compare_exchange(head, next, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
+                record.hazard.store(ptr::null_mut(), Ordering::SeqCst);
+                // CAS 成功的线程独占这个结点的数据，其他线程最多还会读它的 next
+                let element = unsafe { ManuallyDrop::take(&mut (*head).data) };
+                self.retire(head);
+                break Some(element);
+            }
+        };
+        record.hazard.store(ptr::null_mut(), Ordering::SeqCst);
+        record.active.store(false, Ordering::Release);
+        result
+    }
+
+    // 占用一个空闲的危险指针记录，没有空闲的就新建一个
+    fn acquire_record(&self) -> &HazardRecord {
+        let mut current = self.hazards.load(Ordering::Acquire);
+        while !current.is_null() {
+            let record = unsafe { &*current };
+            if record.active.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_ok() {
+                return record;
+            }
+            current = record.next;
+        }
+        let record = Box::into_raw(Box::new(HazardRecord {
+            hazard: AtomicPtr::new(ptr::null_mut()),
+            active: AtomicBool::new(true),
+            next: ptr::null_mut(),
+        }));
+        let mut head = self.hazards.load(Ordering::Acquire);
+        loop {
+            unsafe { (*record).next = head };
+            match self.hazards.compare_exchange_weak(head, record, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => break,
+                Err(current) => head = current,
+            }
+        }
+        self.hazard_count.fetch_add(1, Ordering::Relaxed);
+        unsafe { &*record }
+    }
+
+    fn retire(&self, node: *mut Node<T>) {
+        // 先计数再放进链表，否则别的线程可能先释放这个结点再减去计数，导致计数下溢
+        let count = self.retired_count.fetch_add(1, Ordering::Relaxed) + 1;
+        self.push_retired(node);
+        if count >= RECLAIM_THRESHOLD + 2 * self.hazard_count.load(Ordering::Relaxed) {
+            self.reclaim();
+        }
+    }
+
+    fn push_retired(&self, node: *mut Node<T>) {
+        let mut head = self.retired.load(Ordering::Acquire);
+        loop {
+            unsafe { (*node).retired_next = head };
+            match self.retired.compare_exchange_weak(head, node, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    // 取走整个待回收链表，释放没有被保护的结点，其余的放回去等下次扫描
+    fn reclaim(&self) {
+        let mut current = self.retired.swap(ptr::null_mut(), Ordering::SeqCst);
+        if current.is_null() {
+            return;
+        }
+        let mut protected = Vec::new();
+        let mut record = self.hazards.load(Ordering::SeqCst);
+        while !record.is_null() {
+            let hazard = unsafe { (*record).hazard.load(Ordering::SeqCst) };
+            if !hazard.is_null() {
+                protected.push(hazard);
+            }
+            record = unsafe { (*record).next };
+        }
+        let mut freed = 0;
+        while !current.is_null() {
+            let next = unsafe { (*current).retired_next };
+            if protected.contains(&current.cast()) {
+                self.push_retired(current);
+            } else {
+                drop(unsafe { Box::from_raw(current) });
+                freed += 1;
+            }
+            current = next;
+        }
+        self.retired_count.fetch_sub(freed, Ordering::Relaxed);
+    }
+}
+
+impl<T> Drop for AtomicLinkedStack<T> {
+    // drop 时没有其他线程在访问，所有结点都可以直接释放
+    fn drop(&mut self) {
+        let mut current = *self.head.get_mut();
+        while !current.is_null() {
+            let mut node = unsafe { Box::from_raw(current) };
+            unsafe { ManuallyDrop::drop(&mut node.data) };
+            current = node.next;
+        }
+        let mut current = *self.retired.get_mut();
+        while !current.is_null() {
+            let node = unsafe { Box::from_raw(current) };
+            current = node.retired_next;
+        }
+        let mut current = *self.hazards.get_mut();
+        while !current.is_null() {
+            let record = unsafe { Box::from_raw(current) };
+            current = record.next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::{Arc, Barrier},
+        thread,
+    };
+
+    #[test]
+    fn test_single_thread() {
+        let stack = AtomicLinkedStack::new();
+        assert!(stack.is_empty());
+        assert_eq!(stack.pop(), None);
+        for i in 0..1000 {
+            stack.push(i);
+        }
+        for i in (0..1000).rev() {
+            assert_eq!(stack.pop(), Some(i));
+        }
+        assert!(stack.is_empty());
+        // 没有其他线程持有危险指针，被 pop 的结点会被及时回收
+        assert!(stack.retired_count.load(Ordering::SeqCst) < RECLAIM_THRESHOLD + 2);
+    }
+
+    #[test]
+    fn test_is_send_and_sync() {
+        fn assert_send_sync<S: Send + Sync>() {}
+        assert_send_sync::<AtomicLinkedStack<String>>();
+    }
+
+    // 每个线程交替 push 和 pop，最后检查所有元素恰好出现一次
+    #[test]
+    fn test_concurrent_push_pop() {
+        const THREADS: usize = 8;
+        const PER_THREAD: usize = 20_000;
+        let stack = Arc::new(AtomicLinkedStack::new());
+        let barrier = Arc::new(Barrier::new(THREADS));
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let stack = Arc::clone(&stack);
+                let barrier = Arc::clone(&barrier);
+                thread::spawn(move || {
+                    barrier.wait();
+                    let mut popped = Vec::new();
+                    for i in 0..PER_THREAD {
+                        stack.push(t * PER_THREAD + i);
+                        if i % 3 != 0
+                            && let Some(value) = stack.pop()
+                        {
+                            popped.push(value);
+                        }
+                    }
+                    popped
+                })
+            })
+            .collect();
+        let mut all: Vec<usize> = handles.into_iter().flat_map(|h| h.join().unwrap()).collect();
+        while let Some(value) = stack.pop() {
+            all.push(value);
+        }
+        all.sort_unstable();
+        assert_eq!(all, (0..THREADS * PER_THREAD).collect::<Vec<_>>());
+    }
+
+    // 一半线程只 push，一半线程只 pop
+    #[test]
+    fn test_producers_and_consumers() {
+        const PRODUCERS: usize = 4;
+        const PER_PRODUCER: usize = 25_000;
+        let stack = Arc::new(AtomicLinkedStack::new());
+        let done = Arc::new(AtomicUsize::new(0));
+        let producers: Vec<_> = (0..PRODUCERS)
+            .map(|p| {
+                let stack = Arc::clone(&stack);
+                let done = Arc::clone(&done);
+                thread::spawn(move || {
+                    for i in 0..PER_PRODUCER {
+                        stack.push(p * PER_PRODUCER + i);
+                    }
+                    done.fetch_add(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        let consumers: Vec<_> = (0..4)
+            .map(|_| {
+                let stack = Arc::clone(&stack);
+                let done = Arc::clone(&done);
+                thread::spawn(move || {
+                    let mut popped = Vec::new();
+                    loop {
+                        match stack.pop() {
+                            Some(value) => popped.push(value),
+                            None if done.load(Ordering::SeqCst) == PRODUCERS => break,
+                            None => thread::yield_now(),
+                        }
+                    }
+                    popped
+                })
+            })
+            .collect();
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        let mut all: Vec<usize> = consumers.into_iter().flat_map(|h| h.join().unwrap()).collect();
+        while let Some(value) = stack.pop() {
+            all.push(value);
+        }
+        all.sort_unstable();
+        assert_eq!(all, (0..PRODUCERS * PER_PRODUCER).collect::<Vec<_>>());
+    }
+
+    // 每个元素都恰好被 drop 一次：被 pop 的由调用者 drop，剩下的在栈 drop 时释放
+    #[test]
+    fn test_every_element_dropped_once() {
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+        static ID_SUM: AtomicUsize = AtomicUsize::new(0);
+        struct Counted(usize);
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::SeqCst);
+                ID_SUM.fetch_add(self.0, Ordering::SeqCst);
+            }
+        }
+
+        let stack = Arc::new(AtomicLinkedStack::new());
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let stack = Arc::clone(&stack);
+                thread::spawn(move || {
+                    for i in 0..5000 {
+                        stack.push(Counted(t * 5000 + i));
+                        if i % 2 == 0 {
+                            drop(stack.pop());
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        drop(Arc::try_unwrap(stack).ok().unwrap());
+        assert_eq!(DROPS.load(Ordering::SeqCst), 20_000);
+        // 编号之和也对得上，说明没有元素被 drop 两次而另一个被漏掉
+        assert_eq!(ID_SUM.load(Ordering::SeqCst), (0..20_000).sum::<usize>());
+    }
+}
@@ -1,5 +1,7 @@
 use std::{marker::PhantomData, mem, ptr::NonNull};
 
+use super::pool::{NodePool, PoolStats};
+
 pub struct DoubleLinkedList<T> {
     // 等同于使用裸指针，但是需要额外注意内存安全问题，Option增加了一定的安全性
     head: Option<NonNull<DoubleLinkedNode<T>>>,
@@ -7,6 +9,8 @@ pub struct DoubleLinkedList<T> {
     len: usize,
     // marker说明这个数据结构对一个Box<Node<T>>持有所有权，并且会负责调用drop
     marker: PhantomData<Box<DoubleLinkedNode<T>>>,
+    // 可选的结点池，pop_front 和 pop_back 弹出的结点留在这里供之后的 push 复用，链表 drop 时结点直接释放
+    pool: NodePool<DoubleLinkedNode<T>>,
 }
 
 struct DoubleLinkedNode<T> {
@@ -20,12 +24,13 @@ impl<T> DoubleLinkedNode<T> {
         Self {
             next: None,
             prev: None,
-            element: element,
+            element,
         }
     }
 
     // 我自己没有设想过，但是非常好的一个设计
     // 这个函数将会消耗Box，相当于将堆上的内容移动到栈上（释放堆内存并将element拷贝到栈上），需要注意的是，实现原理上和take方法并不相似，take方法是取出对应位置的数据并给原来位置上的数据设置为None，也就是说take会保留容器，而into_element是消费方法，会使得Box对应的堆空间释放
+    #[allow(clippy::boxed_local)]
     fn into_element(self: Box<Self>) -> T {
         self.element
     }
@@ -34,7 +39,20 @@ impl<T> DoubleLinkedNode<T> {
 impl<T> DoubleLinkedList<T> {
     // 创建一个空的双链表
     pub const fn new() -> Self {
-        Self { head: None, tail: None, len: 0, marker: PhantomData }
+        Self::with_pool_capacity(0)
+    }
+
+    // 弹出的结点最多保留 capacity 个，push 时直接复用
+    pub const fn with_pool_capacity(capacity: usize) -> Self {
+        Self { head: None, tail: None, len: 0, marker: PhantomData, pool: NodePool::with_capacity(capacity) }
+    }
+
+    pub fn shrink_pool(&mut self) {
+        self.pool.shrink();
+    }
+
+    pub fn pool_stats(&self) -> PoolStats {
+        self.pool.stats()
     }
 
     fn push_front_node(&mut self, mut node: Box<DoubleLinkedNode<T>>) {
@@ -57,7 +75,8 @@ impl<T> DoubleLinkedList<T> {
     }
 
     pub fn push_front(&mut self, elt: T) {
-        self.push_front_node(Box::new(DoubleLinkedNode::new(elt)));
+        let node = self.pool.alloc(DoubleLinkedNode::new(elt));
+        self.push_front_node(node);
     }
 
     fn pop_front_node(&mut self) -> Option<Box<DoubleLinkedNode<T>>> {
@@ -78,8 +97,15 @@ impl<T> DoubleLinkedList<T> {
         })
     }
 
+    // 弹出的结点和 pop_back 一样交给结点池回收
+    pub fn pop_front(&mut self) -> Option<T> {
+        let node = self.pop_front_node()?;
+        Some(self.release(node))
+    }
+
     pub fn push_back(&mut self, elt: T) {
-        self.push_back_node(Box::new(DoubleLinkedNode::new(elt)))
+        let node = self.pool.alloc(DoubleLinkedNode::new(elt));
+        self.push_back_node(node)
     }
 
     fn push_back_node(&mut self, mut node: Box<DoubleLinkedNode<T>>) {
@@ -99,7 +125,13 @@ impl<T> DoubleLinkedList<T> {
     }
 
     pub fn pop_back(&mut self) -> Option<T> {
-        self.pop_back_node().map(DoubleLinkedNode::into_element)
+        let node = self.pop_back_node()?;
+        Some(self.release(node))
+    }
+
+    // 没有启用结点池时直接用 into_element 消耗 Box，否则交给结点池回收
+    fn release(&mut self, node: Box<DoubleLinkedNode<T>>) -> T {
+        if self.pool.is_enabled() { self.pool.recycle(node).element } else { node.into_element() }
     }
 
     // 和删除头结点类似
@@ -118,6 +150,7 @@ impl<T> DoubleLinkedList<T> {
         })
     }
 
+    #[allow(dead_code)]
     unsafe fn unlink_node(&mut self, mut node: NonNull<DoubleLinkedNode<T>>) {
         let node = unsafe {
             node.as_mut()
@@ -136,6 +169,12 @@ impl<T> DoubleLinkedList<T> {
     }
 }
 
+impl<T> Default for DoubleLinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> Drop for DoubleLinkedList<T> {
     fn drop(&mut self) {
         /// 使用 **防御性临时变量** 模式防止析构过程中出现异常导致资源泄漏（定义一个内部结构体作为“守卫”，它持有一个指向当前链表的可变引用。）
@@ -159,4 +198,43 @@ impl<T> Drop for DoubleLinkedList<T> {
             mem::forget(guard);
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_reuses_nodes() {
+        let mut list = DoubleLinkedList::with_pool_capacity(2);
+        list.push_back(1);
+        list.push_front(0);
+        list.push_back(2);
+        assert_eq!(list.pop_back(), Some(2));
+        assert_eq!(list.pop_back(), Some(1));
+        list.push_front(-1);
+        assert_eq!(list.pop_back(), Some(0));
+        assert_eq!(list.pop_back(), Some(-1));
+        assert_eq!(list.pop_back(), None);
+        assert_eq!(list.pool_stats(), PoolStats { allocations: 3, reuses: 1, pooled: 2, capacity: 2 });
+        list.shrink_pool();
+        assert_eq!(list.pool_stats().pooled, 0);
+    }
+
+    #[test]
+    fn test_pop_front_recycles() {
+        let mut list = DoubleLinkedList::with_pool_capacity(4);
+        for i in 0..4 {
+            list.push_back(i);
+        }
+        assert_eq!(list.pop_front(), Some(0));
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pool_stats().pooled, 3);
+        list.push_front(9);
+        assert_eq!(list.pop_front(), Some(9));
+        assert_eq!(list.pop_front(), Some(2));
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.pool_stats(), PoolStats { allocations: 4, reuses: 1, pooled: 4, capacity: 4 });
+    }
+}
@@ -0,0 +1,713 @@
+// 撤销/重做历史，编辑器中最常见的栈应用
+// 执行过的命令压入撤销栈；撤销时从撤销栈弹出并压入重做栈，重做时再放回撤销栈；
+// 执行新命令之后，重做栈中的记录就失效了，所以要清空
+
+use std::mem;
+
+use super::{LinkedStack, SequentialStack, stack::Stack};
+
+pub trait Command {
+    // 命令作用的对象，例如编辑器的文本缓冲区
+    type Target;
+
+    fn apply(&mut self, target: &mut Self::Target);
+
+    fn undo(&mut self, target: &mut Self::Target);
+
+    // 把紧接着执行的 next 合并到自己身上，合并之后撤销一次就相当于撤销了两条命令
+    // 例如连续输入的字符可以合并成一次输入，不能合并时原样返回 next
+    fn merge(&mut self, next: Self) -> Result<(), Self>
+    where
+        Self: Sized,
+    {
+        Err(next)
+    }
+
+    // 命令占用的内存（字节数的估计值），按内存限制历史记录时使用
+    fn size(&self) -> usize {
+        mem::size_of_val(self)
+    }
+}
+
+// 撤销栈和重做栈中的一条记录，普通命令的记录只有一条命令，事务的记录有多条命令
+pub struct Entry<C> {
+    commands: Vec<C>,
+    size: usize,
+}
+
+impl<C: Command> Entry<C> {
+    fn new() -> Self {
+        Self { commands: Vec::new(), size: 0 }
+    }
+
+    fn push(&mut self, command: C) {
+        self.size += command.size();
+        self.commands.push(command);
+    }
+
+    // 尝试和最后一条命令合并，返回合并后记录大小的变化
+    fn merge(&mut self, command: C) -> Result<isize, C> {
+        let Some(last) = self.commands.last_mut() else {
+            return Err(command);
+        };
+        let before = last.size();
+        last.merge(command)?;
+        let after = last.size();
+        self.size = self.size - before + after;
+        Ok(after as isize - before as isize)
+    }
+
+    // 逆序撤销
+    fn undo(&mut self, target: &mut C::Target) {
+        for command in self.commands.iter_mut().rev() {
+            command.undo(target);
+        }
+    }
+
+    fn apply(&mut self, target: &mut C::Target) {
+        for command in self.commands.iter_mut() {
+            command.apply(target);
+        }
+    }
+}
+
+// 历史记录使用的栈，除了栈的基本操作，还要能丢弃最旧的记录
+// 约定 push 只在 len 达到 capacity 时失败，History 在修改目标之前据此检查，保证记录不会丢失
+pub trait HistoryStack<T>: Stack<T> + Default {
+    fn pop_bottom(&mut self) -> Result<T, &'static str>;
+}
+
+impl<T> HistoryStack<T> for LinkedStack<T> {
+    fn pop_bottom(&mut self) -> Result<T, &'static str> {
+        LinkedStack::pop_bottom(self)
+    }
+}
+
+impl<T, const N: usize> HistoryStack<T> for SequentialStack<T, N> {
+    fn pop_bottom(&mut self) -> Result<T, &'static str> {
+        SequentialStack::pop_bottom(self)
+    }
+}
+
+// 默认使用链栈，记录条数只受 max_entries 和 max_memory 限制，丢弃最旧的记录均摊 O(1)
+pub struct History<C: Command, S: HistoryStack<Entry<C>> = LinkedStack<Entry<C>>> {
+    undo: S,
+    redo: S,
+    // 正在进行的事务，事务中的命令提交时作为一条记录压入撤销栈
+    transaction: Option<Entry<C>>,
+    // 为 false 时新命令不会和撤销栈顶的记录合并
+    mergeable: bool,
+    max_entries: Option<usize>,
+    max_memory: Option<usize>,
+    // 撤销栈、重做栈和未提交事务中所有命令的 size 之和
+    memory: usize,
+}
+
+// 有界模式：两个栈都是容量为 N 的顺序栈，最多保存 N 条记录，超出时丢弃最旧的记录
+pub type BoundedHistory<C, const N: usize> = History<C, SequentialStack<Entry<C>, N>>;
+
+impl<C: Command, S: HistoryStack<Entry<C>>> Default for History<C, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Command, S: HistoryStack<Entry<C>>> History<C, S> {
+    pub fn new() -> Self {
+        Self {
+            undo: S::default(),
+            redo: S::default(),
+            transaction: None,
+            mergeable: false,
+            max_entries: None,
+            max_memory: None,
+            memory: 0,
+        }
+    }
+
+    // 撤销栈和重做栈合计最多保存多少条记录，None 表示不限制
+    pub fn set_max_entries(&mut self, max_entries: Option<usize>) {
+        self.max_entries = max_entries;
+        self.enforce_limits();
+    }
+
+    // 所有记录合计最多占用多少内存，None 表示不限制
+    pub fn set_max_memory(&mut self, max_memory: Option<usize>) {
+        self.max_memory = max_memory;
+        self.enforce_limits();
+    }
+
+    pub fn max_entries(&self) -> Option<usize> {
+        self.max_entries
+    }
+
+    pub fn max_memory(&self) -> Option<usize> {
+        self.max_memory
+    }
+
+    // 执行一条新命令并记录下来，重做栈会被清空
+    pub fn apply(&mut self, target: &mut C::Target, mut command: C) {
+        command.apply(target);
+        self.clear_redo();
+
+        if let Some(transaction) = &mut self.transaction {
+            match transaction.merge(command) {
+                Ok(delta) => self.memory = self.memory.saturating_add_signed(delta),
+                Err(command) => {
+                    self.memory += command.size();
+                    transaction.push(command);
+                }
+            }
+            return;
+        }
+
+        if self.mergeable
+            && let Ok(top) = self.undo.peek_mut()
+            && top.commands.len() == 1
+        {
+            match top.merge(command) {
+                Ok(delta) => {
+                    self.memory = self.memory.saturating_add_signed(delta);
+                    self.enforce_limits();
+                    return;
+                }
+                Err(rejected) => command = rejected,
+            }
+        }
+
+        let mut entry = Entry::new();
+        self.memory += command.size();
+        entry.push(command);
+        self.record(entry);
+        self.mergeable = true;
+    }
+
+    // 之后的命令不再和当前的最后一条记录合并，例如编辑器在光标移动之后调用
+    pub fn seal(&mut self) {
+        self.mergeable = false;
+    }
+
+    pub fn undo(&mut self, target: &mut C::Target) -> Result<(), &'static str> {
+        if self.transaction.is_some() {
+            return Err("Transaction in progress!");
+        }
+        if self.undo.is_empty() {
+            return Err("Nothing to undo!");
+        }
+        // 先确认重做栈放得下，再修改目标，否则弹出的记录无处安放，目标也和记录对不上
+        if is_full(&self.redo) {
+            return Err("Redo stack is full!");
+        }
+        let mut entry = self.undo.pop()?;
+        entry.undo(target);
+        self.redo.push(entry).expect("redo stack has room");
+        self.mergeable = false;
+        Ok(())
+    }
+
+    pub fn redo(&mut self, target: &mut C::Target) -> Result<(), &'static str> {
+        if self.transaction.is_some() {
+            return Err("Transaction in progress!");
+        }
+        if self.redo.is_empty() {
+            return Err("Nothing to redo!");
+        }
+        if is_full(&self.undo) {
+            return Err("Undo stack is full!");
+        }
+        let mut entry = self.redo.pop()?;
+        entry.apply(target);
+        self.undo.push(entry).expect("undo stack has room");
+        self.mergeable = false;
+        Ok(())
+    }
+
+    // 开始一个事务，提交之前执行的所有命令会作为一条记录一起撤销和重做
+    pub fn begin_transaction(&mut self) -> Result<(), &'static str> {
+        if self.transaction.is_some() {
+            return Err("Transaction in progress!");
+        }
+        self.transaction = Some(Entry::new());
+        Ok(())
+    }
+
+    // 提交事务，空事务不会留下记录
+    // 事务本身超出内存限制时提交失败，事务保持打开，可以回滚或者放宽限制后再提交
+    pub fn commit_transaction(&mut self) -> Result<(), &'static str> {
+        let size = self.transaction.as_ref().ok_or("No transaction in progress!")?.size;
+        if self.max_memory.is_some_and(|max| size > max) {
+            return Err("Transaction exceeds memory limit!");
+        }
+        let entry = self.transaction.take().unwrap();
+        if !entry.commands.is_empty() {
+            self.record(entry);
+            self.mergeable = false;
+        }
+        Ok(())
+    }
+
+    // 放弃事务，撤销事务中已经执行的命令
+    // 和提交一样，之后的命令不会再和事务之前的记录合并
+    pub fn rollback_transaction(&mut self, target: &mut C::Target) -> Result<(), &'static str> {
+        let mut entry = self.transaction.take().ok_or("No transaction in progress!")?;
+        entry.undo(target);
+        self.memory -= entry.size;
+        self.mergeable = false;
+        Ok(())
+    }
+
+    pub fn in_transaction(&self) -> bool {
+        self.transaction.is_some()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    // 可以撤销的记录条数
+    pub fn undo_len(&self) -> usize {
+        self.undo.len()
+    }
+
+    pub fn redo_len(&self) -> usize {
+        self.redo.len()
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        self.memory
+    }
+
+    // 清空所有记录，未提交的事务不受影响
+    pub fn clear(&mut self) {
+        while let Ok(entry) = self.undo.pop() {
+            self.memory -= entry.size;
+        }
+        self.clear_redo();
+        self.mergeable = false;
+    }
+
+    fn clear_redo(&mut self) {
+        while let Ok(entry) = self.redo.pop() {
+            self.memory -= entry.size;
+        }
+    }
+
+    // 把一条新记录压入撤销栈，调用之前重做栈已经被清空
+    // entry 的内存在这之前已经计入 memory
+    fn record(&mut self, entry: Entry<C>) {
+        // 有界模式下撤销栈满了，先腾出一个位置
+        if let Some(capacity) = self.undo.capacity()
+            && self.undo.len() >= capacity
+            && let Ok(oldest) = self.undo.pop_bottom()
+        {
+            self.memory -= oldest.size;
+        }
+        let size = entry.size;
+        if self.undo.push(entry).is_err() {
+            // 只有容量为 0 时才会失败，这条记录无法保存
+            self.memory -= size;
+        }
+        self.enforce_limits();
+    }
+
+    // 超出限制时先丢弃撤销栈中最旧的记录，撤销栈空了再丢弃重做栈中最远的记录
+    // 未提交的事务不计入限制，提交时再检查
+    fn enforce_limits(&mut self) {
+        let pending = self.transaction.as_ref().map_or(0, |transaction| transaction.size);
+        loop {
+            let over_entries = self.max_entries.is_some_and(|max| self.undo.len() + self.redo.len() > max);
+            let over_memory = self.max_memory.is_some_and(|max| self.memory - pending > max);
+            if !over_entries && !over_memory {
+                break;
+            }
+            match self.undo.pop_bottom().or_else(|_| self.redo.pop_bottom()) {
+                Ok(entry) => self.memory -= entry.size,
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+fn is_full<T, S: HistoryStack<T>>(stack: &S) -> bool {
+    stack.capacity().is_some_and(|capacity| stack.len() >= capacity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linear_structure::test_rng::TestRng;
+
+    // 文本编辑命令，连续的输入可以合并
+    #[derive(Debug)]
+    enum Edit {
+        Insert { pos: usize, text: String },
+        Delete { pos: usize, text: String },
+    }
+
+    fn insert(pos: usize, text: &str) -> Edit {
+        Edit::Insert { pos, text: text.to_string() }
+    }
+
+    impl Command for Edit {
+        type Target = String;
+
+        fn apply(&mut self, buffer: &mut String) {
+            match self {
+                Edit::Insert { pos, text } => buffer.insert_str(*pos, text),
+                Edit::Delete { pos, text } => buffer.replace_range(*pos..*pos + text.len(), ""),
+            }
+        }
+
+        fn undo(&mut self, buffer: &mut String) {
+            match self {
+                Edit::Insert { pos, text } => buffer.replace_range(*pos..*pos + text.len(), ""),
+                Edit::Delete { pos, text } => buffer.insert_str(*pos, text),
+            }
+        }
+
+        fn merge(&mut self, next: Self) -> Result<(), Self> {
+            match (self, next) {
+                (Edit::Insert { pos, text }, Edit::Insert { pos: next_pos, text: next_text }) if *pos + text.len() == next_pos => {
+                    text.push_str(&next_text);
+                    Ok(())
+                }
+                (_, next) => Err(next),
+            }
+        }
+
+        fn size(&self) -> usize {
+            match self {
+                Edit::Insert { text, .. } | Edit::Delete { text, .. } => mem::size_of::<Self>() + text.len(),
+            }
+        }
+    }
+
+    fn type_word(history: &mut History<Edit>, buffer: &mut String, word: &str) {
+        for c in word.chars() {
+            let pos = buffer.len();
+            history.apply(buffer, Edit::Insert { pos, text: c.to_string() });
+        }
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut buffer = String::new();
+        let mut history: History<Edit> = History::new();
+        assert_eq!(history.undo(&mut buffer), Err("Nothing to undo!"));
+
+        history.apply(&mut buffer, insert(0, "hello"));
+        history.seal();
+        history.apply(&mut buffer, Edit::Delete { pos: 0, text: "he".to_string() });
+        assert_eq!(buffer, "llo");
+
+        history.undo(&mut buffer).unwrap();
+        assert_eq!(buffer, "hello");
+        history.undo(&mut buffer).unwrap();
+        assert_eq!(buffer, "");
+        assert!(!history.can_undo());
+        history.redo(&mut buffer).unwrap();
+        assert_eq!(buffer, "hello");
+        assert_eq!(history.redo_len(), 1);
+
+        // 新命令会清空重做栈
+        history.apply(&mut buffer, insert(5, "!"));
+        assert_eq!(buffer, "hello!");
+        assert!(!history.can_redo());
+        assert_eq!(history.redo(&mut buffer), Err("Nothing to redo!"));
+    }
+
+    #[test]
+    fn test_merge_typing() {
+        let mut buffer = String::new();
+        let mut history: History<Edit> = History::new();
+        type_word(&mut history, &mut buffer, "hello");
+        assert_eq!(history.undo_len(), 1);
+        history.seal();
+        type_word(&mut history, &mut buffer, " world");
+        assert_eq!(history.undo_len(), 2);
+
+        history.undo(&mut buffer).unwrap();
+        assert_eq!(buffer, "hello");
+        // 撤销之后的输入不会并入之前的记录
+        type_word(&mut history, &mut buffer, "!");
+        assert_eq!(history.undo_len(), 2);
+        history.undo(&mut buffer).unwrap();
+        assert_eq!(buffer, "hello");
+        history.undo(&mut buffer).unwrap();
+        assert_eq!(buffer, "");
+    }
+
+    #[test]
+    fn test_transactions() {
+        let mut buffer = String::from("abc");
+        let mut history: History<Edit> = History::new();
+
+        history.begin_transaction().unwrap();
+        assert_eq!(history.begin_transaction(), Err("Transaction in progress!"));
+        history.apply(&mut buffer, insert(0, "<"));
+        history.apply(&mut buffer, insert(4, ">"));
+        assert_eq!(history.undo(&mut buffer), Err("Transaction in progress!"));
+        history.commit_transaction().unwrap();
+        assert_eq!(buffer, "<abc>");
+        assert_eq!(history.undo_len(), 1);
+
+        history.undo(&mut buffer).unwrap();
+        assert_eq!(buffer, "abc");
+        history.redo(&mut buffer).unwrap();
+        assert_eq!(buffer, "<abc>");
+
+        history.begin_transaction().unwrap();
+        history.apply(&mut buffer, Edit::Delete { pos: 0, text: "<".to_string() });
+        history.rollback_transaction(&mut buffer).unwrap();
+        assert_eq!(buffer, "<abc>");
+        assert_eq!(history.undo_len(), 1);
+        assert_eq!(history.commit_transaction(), Err("No transaction in progress!"));
+
+        // 空事务不留下记录
+        history.begin_transaction().unwrap();
+        history.commit_transaction().unwrap();
+        assert_eq!(history.undo_len(), 1);
+    }
+
+    #[test]
+    fn test_rollback_stops_merging() {
+        let mut buffer = String::new();
+        let mut history: History<Edit> = History::new();
+        type_word(&mut history, &mut buffer, "hello");
+        history.begin_transaction().unwrap();
+        history.apply(&mut buffer, insert(0, "> "));
+        history.rollback_transaction(&mut buffer).unwrap();
+        assert_eq!(buffer, "hello");
+
+        // 回滚之后的输入是一条新记录，不会并入回滚之前的输入
+        type_word(&mut history, &mut buffer, "!");
+        assert_eq!(history.undo_len(), 2);
+        history.undo(&mut buffer).unwrap();
+        assert_eq!(buffer, "hello");
+    }
+
+    #[test]
+    fn test_max_entries() {
+        let mut buffer = String::new();
+        let mut history: History<Edit> = History::new();
+        history.set_max_entries(Some(3));
+        for c in ["a", "b", "c", "d", "e"] {
+            let edit = insert(buffer.len(), c);
+            history.apply(&mut buffer, edit);
+            history.seal();
+        }
+        assert_eq!(history.undo_len(), 3);
+        while history.undo(&mut buffer).is_ok() {}
+        assert_eq!(buffer, "ab");
+
+        // 降低上限时立即丢弃多余的记录
+        history.redo(&mut buffer).unwrap();
+        history.set_max_entries(Some(1));
+        assert_eq!(history.undo_len() + history.redo_len(), 1);
+        assert!(history.undo(&mut buffer).is_err());
+    }
+
+    #[test]
+    fn test_max_memory() {
+        let mut buffer = String::new();
+        let mut history: History<Edit> = History::new();
+        let unit = mem::size_of::<Edit>();
+        history.set_max_memory(Some(2 * unit + 20));
+        history.apply(&mut buffer, insert(0, &"x".repeat(10)));
+        history.seal();
+        history.apply(&mut buffer, insert(10, &"y".repeat(10)));
+        history.seal();
+        assert_eq!(history.memory_usage(), 2 * unit + 20);
+        history.apply(&mut buffer, insert(20, "z"));
+        assert_eq!(history.undo_len(), 2);
+        assert_eq!(history.memory_usage(), 2 * unit + 11);
+
+        // 合并让记录变大时也会检查内存限制
+        type_word(&mut history, &mut buffer, &"z".repeat(10));
+        assert_eq!(history.undo_len(), 1);
+        assert_eq!(history.memory_usage(), unit + 11);
+        history.clear();
+        assert_eq!(history.memory_usage(), 0);
+    }
+
+    #[test]
+    fn test_transaction_memory_limit() {
+        let mut buffer = String::new();
+        let mut history: History<Edit> = History::new();
+        let unit = mem::size_of::<Edit>();
+        history.apply(&mut buffer, insert(0, "a"));
+        history.seal();
+        history.begin_transaction().unwrap();
+        history.apply(&mut buffer, insert(1, &"b".repeat(10)));
+        history.apply(&mut buffer, Edit::Delete { pos: 0, text: "a".to_string() });
+
+        // 事务中降低上限不会因为事务占用的内存丢弃已有的记录
+        history.set_max_memory(Some(2 * unit + 5));
+        assert_eq!(history.undo_len(), 1);
+
+        // 事务本身超出上限，提交失败，已有记录和事务都保留
+        assert_eq!(history.commit_transaction(), Err("Transaction exceeds memory limit!"));
+        assert!(history.in_transaction());
+        assert_eq!(history.undo_len(), 1);
+        history.rollback_transaction(&mut buffer).unwrap();
+        assert_eq!(buffer, "a");
+        assert_eq!(history.memory_usage(), unit + 1);
+
+        // 放得下的事务提交之后照常丢弃旧记录腾出空间
+        history.begin_transaction().unwrap();
+        history.apply(&mut buffer, insert(1, "b"));
+        history.apply(&mut buffer, insert(0, "c"));
+        history.commit_transaction().unwrap();
+        assert_eq!(history.undo_len(), 1);
+        assert_eq!(history.memory_usage(), 2 * unit + 2);
+        history.undo(&mut buffer).unwrap();
+        assert_eq!(buffer, "a");
+    }
+
+    #[test]
+    fn test_bounded_history() {
+        let mut buffer = String::new();
+        let mut history: BoundedHistory<Edit, 4> = BoundedHistory::new();
+        for i in 0..10 {
+            let edit = insert(buffer.len(), &i.to_string());
+            history.apply(&mut buffer, edit);
+            history.seal();
+        }
+        assert_eq!(history.undo_len(), 4);
+        for _ in 0..4 {
+            history.undo(&mut buffer).unwrap();
+        }
+        assert_eq!(buffer, "012345");
+        assert!(history.undo(&mut buffer).is_err());
+        for _ in 0..4 {
+            history.redo(&mut buffer).unwrap();
+        }
+        assert_eq!(buffer, "0123456789");
+
+        let mut empty: BoundedHistory<Edit, 0> = BoundedHistory::new();
+        empty.apply(&mut buffer, insert(0, "-"));
+        assert!(!empty.can_undo());
+        assert_eq!(empty.memory_usage(), 0);
+    }
+
+    #[test]
+    fn test_full_stack_keeps_entry() {
+        let mut buffer = String::new();
+        let mut history: BoundedHistory<Edit, 2> = BoundedHistory::new();
+        history.apply(&mut buffer, insert(0, "ab"));
+        history.seal();
+        history.apply(&mut buffer, insert(2, "cd"));
+        // 正常使用时两个栈合计不会超过容量，这里直接填满重做栈
+        for _ in 0..2 {
+            let mut entry = Entry::new();
+            entry.push(insert(0, "x"));
+            history.redo.push(entry).unwrap();
+        }
+        assert_eq!(history.undo(&mut buffer), Err("Redo stack is full!"));
+        assert_eq!(buffer, "abcd");
+        assert_eq!(history.undo_len(), 2);
+
+        history.undo.pop().unwrap();
+        history.undo.push(Entry::new()).unwrap();
+        assert_eq!(history.redo(&mut buffer), Err("Undo stack is full!"));
+        assert_eq!(buffer, "abcd");
+        assert_eq!(history.redo_len(), 2);
+    }
+
+    // 往 Vec 末尾添加一个数
+    struct Append(u32);
+
+    impl Command for Append {
+        type Target = Vec<u32>;
+
+        fn apply(&mut self, target: &mut Vec<u32>) {
+            target.push(self.0);
+        }
+
+        fn undo(&mut self, target: &mut Vec<u32>) {
+            assert_eq!(target.pop(), Some(self.0));
+        }
+    }
+
+    #[test]
+    fn test_long_sequence() {
+        const COUNT: u32 = 100_000;
+        let mut target = Vec::new();
+        let mut history: History<Append> = History::new();
+        for i in 0..COUNT {
+            history.apply(&mut target, Append(i));
+        }
+        while history.undo(&mut target).is_ok() {}
+        assert!(target.is_empty());
+        while history.redo(&mut target).is_ok() {}
+        assert_eq!(target.len(), COUNT as usize);
+        assert_eq!(history.memory_usage(), COUNT as usize * mem::size_of::<Append>());
+    }
+
+    // 随机地执行、撤销、重做、开启事务，和保存了每个历史状态的模型比较
+    fn random_operations<S: HistoryStack<Entry<Append>>>(history: &mut History<Append, S>, limit: Option<usize>) {
+        let mut target = Vec::new();
+        // states[cursor] 是当前状态，前面的可以撤销回去，后面的可以重做
+        let mut states: Vec<Vec<u32>> = vec![Vec::new()];
+        let mut cursor = 0;
+        let mut rng = TestRng::new(12345);
+        for step in 0..20_000 {
+            let seed = rng.next_u32();
+            match (seed >> 16) % 8 {
+                0..=3 => {
+                    history.apply(&mut target, Append(step));
+                    states.truncate(cursor + 1);
+                    states.push(target.clone());
+                    cursor += 1;
+                }
+                4 => {
+                    history.begin_transaction().unwrap();
+                    for i in 0..(seed >> 20) % 4 {
+                        history.apply(&mut target, Append(step + i));
+                    }
+                    let before = target.len();
+                    history.commit_transaction().unwrap();
+                    if before != states[cursor].len() {
+                        states.truncate(cursor + 1);
+                        states.push(target.clone());
+                        cursor += 1;
+                    }
+                }
+                5 | 6 => {
+                    if history.undo(&mut target).is_ok() {
+                        cursor -= 1;
+                    }
+                }
+                _ => {
+                    if history.redo(&mut target).is_ok() {
+                        cursor += 1;
+                    }
+                }
+            }
+            // 超出上限时模型也丢弃最旧的状态
+            if let Some(limit) = limit
+                && states.len() > limit + 1
+            {
+                let excess = states.len() - limit - 1;
+                states.drain(..excess);
+                cursor -= excess;
+            }
+            assert_eq!(target, states[cursor]);
+            assert_eq!(history.undo_len(), cursor);
+            assert_eq!(history.redo_len(), states.len() - 1 - cursor);
+        }
+    }
+
+    #[test]
+    fn test_random_operations_match_model() {
+        random_operations(&mut History::<Append>::new(), None);
+        let mut limited = History::<Append>::new();
+        limited.set_max_entries(Some(16));
+        random_operations(&mut limited, Some(16));
+        random_operations(&mut BoundedHistory::<Append, 16>::new(), Some(16));
+    }
+}
@@ -1,4 +1,9 @@
-use std::ptr::NonNull;
+use std::{fmt, marker::PhantomData, ptr::NonNull};
+
+use super::{
+    pool::{NodePool, PoolStats},
+    stack::Stack,
+};
 
 struct StackNode<T> {
     data: T,
@@ -7,7 +12,10 @@ struct StackNode<T> {
 
 pub struct LinkedStack<T> {
     len: usize,
-    head: Option<NonNull<StackNode<T>>>
+    head: Option<NonNull<StackNode<T>>>,
+    pool: NodePool<StackNode<T>>,
+    // 栈底附近的若干个结点，从上到下排列，最后一个就是栈底结点，供 pop_bottom 使用
+    bottom: Vec<NonNull<StackNode<T>>>,
 }
 
 impl<T> StackNode<T> {
@@ -17,29 +25,56 @@ impl<T> StackNode<T> {
 }
 
 impl<T> LinkedStack<T> {
-    fn new() -> Self {
-        Self { len: 0, head: None }
+    pub fn new() -> Self {
+        Self::with_pool_capacity(0)
+    }
+
+    // 出栈释放的结点最多保留 capacity 个，之后入栈时直接复用，适合频繁 push/pop 的场景
+    pub fn with_pool_capacity(capacity: usize) -> Self {
+        Self { len: 0, head: None, pool: NodePool::with_capacity(capacity), bottom: Vec::new() }
+    }
+
+    // 把结点池中的空闲结点全部释放
+    pub fn shrink_pool(&mut self) {
+        self.pool.shrink();
+    }
+
+    pub fn pool_stats(&self) -> PoolStats {
+        self.pool.stats()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
     }
 
-    fn push(&mut self, element: T) {
-        let mut node = Box::new(StackNode::new(element));
+    // 链栈没有容量上限，push 总是成功
+    pub fn push(&mut self, element: T) {
+        let mut node = self.pool.alloc(StackNode::new(element));
         node.next = self.head;
         self.head = NonNull::new(Box::into_raw(node));
         self.len += 1;
     }
 
-    fn pop(&mut self) -> Result<T, &'static str> {
+    pub fn pop(&mut self) -> Result<T, &'static str> {
         if self.len == 0 {
             Err("Stack is empty!")
         } else {
             let node = unsafe { Box::from_raw(self.head.unwrap().as_mut()) };
             self.head = node.next;
             self.len -= 1;
-            Ok(node.data)
+            // 弹出的结点也在缓存中，说明缓存已经覆盖到栈顶，直接作废，下次 pop_bottom 时重新建立
+            if self.bottom.len() > self.len {
+                self.bottom.clear();
+            }
+            Ok(self.pool.recycle(node).data)
         }
     }
 
-    fn get(&self) -> Result<&T, &'static str> {
+    pub fn get(&self) -> Result<&T, &'static str> {
         if self.len == 0 {
             Err("Stack is empty!")
         } else {
@@ -47,11 +82,190 @@ impl<T> LinkedStack<T> {
             Ok(&node.data)
         }
     }
+
+    pub fn get_mut(&mut self) -> Result<&mut T, &'static str> {
+        if self.len == 0 {
+            Err("Stack is empty!")
+        } else {
+            let node = unsafe { self.head.unwrap().as_mut() };
+            Ok(&mut node.data)
+        }
+    }
+
+    // 和 get 相同，与 Stack trait 的命名保持一致
+    pub fn peek(&self) -> Result<&T, &'static str> {
+        self.get()
+    }
+
+    pub fn peek_mut(&mut self) -> Result<&mut T, &'static str> {
+        self.get_mut()
+    }
+
+    // 逐个出栈释放结点，不会因为递归 drop 太长的链表而栈溢出
+    pub fn clear(&mut self) {
+        while self.pop().is_ok() {}
+    }
+
+    // 取出栈底元素，均摊 O(1)
+    // 单链表只能从栈顶走到栈底，所以每次遍历时把下面一半的结点缓存下来，之后的 pop_bottom 直接使用，
+    // 一次 O(n) 的遍历分摊到接下来至少 n/2 - 1 次 pop_bottom 上；push 不影响缓存，pop 弹到缓存的范围时才作废
+    pub(crate) fn pop_bottom(&mut self) -> Result<T, &'static str> {
+        if self.len <= 1 {
+            return self.pop();
+        }
+        if self.bottom.len() < 2 {
+            let skip = self.len - (self.len / 2).max(2);
+            self.bottom.clear();
+            let mut current = self.head;
+            for depth in 0..self.len {
+                let node = current.unwrap();
+                if depth >= skip {
+                    self.bottom.push(node);
+                }
+                current = unsafe { node.as_ref().next };
+            }
+        }
+        let last = self.bottom.pop().unwrap();
+        unsafe {
+            // 缓存中的上一个结点成为新的栈底
+            (*self.bottom.last().unwrap().as_ptr()).next = None;
+            self.len -= 1;
+            Ok(self.pool.recycle(Box::from_raw(last.as_ptr())).data)
+        }
+    }
+
+    // 从栈顶到栈底遍历
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { next: self.head, remaining: self.len, marker: PhantomData }
+    }
+}
+
+pub struct Iter<'a, T> {
+    next: Option<NonNull<StackNode<T>>>,
+    remaining: usize,
+    marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.next.map(|ptr| {
+            let node = unsafe { ptr.as_ref() };
+            self.next = node.next;
+            self.remaining -= 1;
+            &node.data
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> Default for Iter<'_, T> {
+    fn default() -> Self {
+        Iter { next: None, remaining: 0, marker: PhantomData }
+    }
+}
+
+// 按照出栈的顺序产生元素
+pub struct IntoIter<T> {
+    stack: LinkedStack<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.stack.pop().ok()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.stack.len, Some(self.stack.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for LinkedStack<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { stack: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LinkedStack<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+// 依次入栈，最后一个元素在栈顶
+impl<T> FromIterator<T> for LinkedStack<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut stack = Self::new();
+        stack.extend(iter);
+        stack
+    }
+}
+
+impl<T> Extend<T> for LinkedStack<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            self.push(element);
+        }
+    }
+}
+
+// 从栈顶到栈底输出
+impl<T: fmt::Debug> fmt::Debug for LinkedStack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Default for LinkedStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Stack<T> for LinkedStack<T> {
+    fn push(&mut self, element: T) -> Result<(), &'static str> {
+        LinkedStack::push(self, element);
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<T, &'static str> {
+        LinkedStack::pop(self)
+    }
+
+    fn peek(&self) -> Result<&T, &'static str> {
+        self.get()
+    }
+
+    fn peek_mut(&mut self) -> Result<&mut T, &'static str> {
+        self.get_mut()
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
 }
 
 impl<T> Drop for LinkedStack<T> {
     fn drop(&mut self) {
-        while self.pop().is_ok() {}
+        // 先换成不保留结点的空池，出栈的结点直接释放
+        self.pool = NodePool::new();
+        self.clear();
     }
 }
 
@@ -113,6 +327,105 @@ mod tests {
     }
 }
 
+#[cfg(test)]
+mod pop_bottom_tests {
+    use super::*;
+
+    #[test]
+    fn test_pop_bottom() {
+        let mut stack = LinkedStack::new();
+        for i in 1..=3 {
+            stack.push(i);
+        }
+        assert_eq!(stack.pop_bottom().unwrap(), 1);
+        assert_eq!(stack.len, 2);
+        assert_eq!(stack.pop_bottom().unwrap(), 2);
+        assert_eq!(stack.pop_bottom().unwrap(), 3);
+        assert!(stack.pop_bottom().is_err());
+        stack.push(4);
+        assert_eq!(*stack.get().unwrap(), 4);
+    }
+
+    #[test]
+    fn test_pop_bottom_walks_once_per_half() {
+        let mut stack: LinkedStack<i32> = (0..100).collect();
+        assert_eq!(stack.pop_bottom().unwrap(), 0);
+        // 一次遍历缓存了下面 50 个结点，用掉了一个
+        assert_eq!(stack.bottom.len(), 49);
+        stack.push(100);
+        assert_eq!(stack.pop().unwrap(), 100);
+        for i in 1..49 {
+            assert_eq!(stack.pop_bottom().unwrap(), i);
+        }
+        assert_eq!(stack.bottom.len(), 1);
+        assert_eq!(stack.pop_bottom().unwrap(), 49);
+        assert_eq!(stack.bottom.len(), 24);
+        assert_eq!(stack.iter().copied().collect::<Vec<_>>(), (50..100).rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_pop_bottom_mixed_with_pop() {
+        // pop 弹到缓存的范围之后缓存作废，之后的 pop_bottom 仍然正确
+        let mut stack = LinkedStack::new();
+        let mut model = std::collections::VecDeque::new();
+        for round in 0..200 {
+            for i in 0..round % 5 {
+                stack.push(round * 10 + i);
+                model.push_back(round * 10 + i);
+            }
+            match round % 3 {
+                0 => assert_eq!(stack.pop().ok(), model.pop_back()),
+                _ => assert_eq!(stack.pop_bottom().ok(), model.pop_front()),
+            }
+            assert_eq!(stack.len(), model.len());
+            assert!(stack.iter().eq(model.iter().rev()));
+        }
+        while let Some(expected) = model.pop_front() {
+            assert_eq!(stack.pop_bottom().unwrap(), expected);
+        }
+        assert!(stack.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod pool_tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_reuses_nodes() {
+        let mut stack = LinkedStack::with_pool_capacity(8);
+        for round in 0..100 {
+            for i in 0..8 {
+                stack.push(round * 8 + i);
+            }
+            for i in (0..8).rev() {
+                assert_eq!(stack.pop().unwrap(), round * 8 + i);
+            }
+        }
+        // 只有第一轮需要向分配器申请结点
+        let stats = stack.pool_stats();
+        assert_eq!(stats.allocations, 8);
+        assert_eq!(stats.reuses, 99 * 8);
+        assert_eq!(stats.pooled, 8);
+        stack.shrink_pool();
+        assert_eq!(stack.pool_stats().pooled, 0);
+    }
+
+    #[test]
+    fn test_pool_capacity_limit() {
+        let mut stack: LinkedStack<String> = (0..10).map(|i| i.to_string()).collect();
+        assert_eq!(stack.pool_stats().capacity, 0);
+        stack.clear();
+        assert_eq!(stack.pool_stats().pooled, 0);
+
+        let mut stack = LinkedStack::with_pool_capacity(3);
+        stack.extend((0..10).map(|i| i.to_string()));
+        assert_eq!(stack.pop_bottom().unwrap(), "0");
+        stack.clear();
+        assert_eq!(stack.pool_stats().pooled, 3);
+    }
+}
+
 #[cfg(test)]
 mod edge_case_tests {
     use super::*;
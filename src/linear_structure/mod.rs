@@ -7,18 +7,37 @@
  * @Description: 这是默认设置,请设置`customMade`, 打开koroFileHeader查看配置 进行设置: https://github.com/OBKoro1/koro1FileHeader/wiki/%E9%85%8D%E7%BD%AE
  */
 
+pub mod pool;
 pub mod double_linked_list;
 pub mod sequence_list;
 pub mod singly_linked_list;
 pub mod sequential_stack;
 pub mod sequential_double_stack;
+pub mod multi_stack;
 pub mod linked_stack;
+pub mod atomic_linked_stack;
+pub mod persistent_stack;
+pub mod stack;
+pub mod overflow;
+pub mod aggregate_stack;
+pub mod history;
 pub mod algorithms;
+mod test_rng;
 
+pub use pool::PoolStats;
 pub use double_linked_list::DoubleLinkedList;
 pub use sequence_list::SeqList;
 pub use singly_linked_list::SinglyLinkedList;
 pub use sequential_stack::SequentialStack;
 pub use sequential_double_stack::SqDoubleStack;
+pub use multi_stack::MultiStack;
 pub use linked_stack::LinkedStack;
-pub use algorithms::fibonacci::fibonacci;
\ No newline at end of file
+pub use atomic_linked_stack::AtomicLinkedStack;
+pub use persistent_stack::{ArcPersistentStack, PersistentStack};
+pub use stack::Stack;
+pub use overflow::{OverflowPolicy, PushOutcome};
+pub use aggregate_stack::{AggregateStack, MaxStack, MinStack};
+pub use history::{BoundedHistory, Command, History};
+pub use algorithms::fibonacci::fibonacci;
+pub use algorithms::big_uint::BigUint;
+pub use algorithms::linear_recurrence::LinearRecurrence;
\ No newline at end of file
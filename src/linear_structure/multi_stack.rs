@@ -0,0 +1,347 @@
+// K 个栈共享同一个定长数组，是 SqDoubleStack 的推广
+// 两种布局：
+// 1. Partitioned：数组被划分成 K 个连续的分区，每个栈只能使用自己的分区，一个分区满了之后可以调用 rebalance 重新划分
+// 2. Linked：每个位置额外记录下一个位置的下标，空闲位置串成空闲链表，任何栈都可以使用任何空闲位置
+
+use super::stack::Stack;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    #[default]
+    Partitioned,
+    Linked,
+}
+
+enum Layout<const N: usize, const K: usize> {
+    // 第 i 个栈的元素在 data[starts[i]..starts[i] + lens[i]]，栈顶在末尾，分区的范围是 starts[i]..ends[i]
+    Partitioned { starts: [usize; K], ends: [usize; K] },
+    // next[i] 是栈中位置 i 下面的元素的位置，或者空闲链表中的下一个空闲位置
+    Linked { next: [Option<usize>; N], tops: [Option<usize>; K], free: Option<usize> },
+}
+
+pub struct MultiStack<T, const N: usize, const K: usize> {
+    data: [Option<T>; N],
+    lens: [usize; K],
+    layout: Layout<N, K>,
+}
+
+impl<T, const N: usize, const K: usize> Default for MultiStack<T, N, K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize, const K: usize> MultiStack<T, N, K> {
+    pub fn new() -> Self {
+        Self::with_strategy(Strategy::Partitioned)
+    }
+
+    pub fn with_strategy(strategy: Strategy) -> Self {
+        let layout = match strategy {
+            Strategy::Partitioned => {
+                let mut starts = [0; K];
+                let mut ends = [0; K];
+                Self::partition(&[0; K], &mut starts, &mut ends);
+                Layout::Partitioned { starts, ends }
+            }
+            Strategy::Linked => {
+                let mut next = [None; N];
+                for (i, slot) in next.iter_mut().enumerate() {
+                    *slot = if i + 1 < N { Some(i + 1) } else { None };
+                }
+                Layout::Linked { next, tops: [None; K], free: if N > 0 { Some(0) } else { None } }
+            }
+        };
+        Self { data: [const { None }; N], lens: [0; K], layout }
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        match self.layout {
+            Layout::Partitioned { .. } => Strategy::Partitioned,
+            Layout::Linked { .. } => Strategy::Linked,
+        }
+    }
+
+    // 每个栈先分到自己的元素个数，剩下的空闲位置平均分配，除不尽的部分给前面的栈
+    fn partition(lens: &[usize; K], starts: &mut [usize; K], ends: &mut [usize; K]) {
+        if K == 0 {
+            return;
+        }
+        let free = N - lens.iter().sum::<usize>();
+        let mut start = 0;
+        for i in 0..K {
+            let share = free / K + usize::from(i < free % K);
+            starts[i] = start;
+            ends[i] = start + lens[i] + share;
+            start = ends[i];
+        }
+    }
+
+    fn check_index(index: usize) -> Result<(), &'static str> {
+        if index < K { Ok(()) } else { Err("Stack index out of range!") }
+    }
+
+    // 某个栈栈顶元素在 data 中的下标
+    fn top_slot(&self, index: usize) -> Result<usize, &'static str> {
+        Self::check_index(index)?;
+        if self.lens[index] == 0 {
+            return Err("Stack is empty!");
+        }
+        match &self.layout {
+            Layout::Partitioned { starts, .. } => Ok(starts[index] + self.lens[index] - 1),
+            Layout::Linked { tops, .. } => Ok(tops[index].unwrap()),
+        }
+    }
+
+    pub fn len(&self, index: usize) -> usize {
+        self.lens.get(index).copied().unwrap_or(0)
+    }
+
+    pub fn is_empty(&self, index: usize) -> bool {
+        self.len(index) == 0
+    }
+
+    // 数组中还剩多少空闲位置
+    pub fn remaining(&self) -> usize {
+        N - self.lens.iter().sum::<usize>()
+    }
+
+    // 某个栈现在还能放下多少个元素，分区布局下只算自己分区中的空闲位置
+    pub fn available(&self, index: usize) -> usize {
+        match &self.layout {
+            _ if index >= K => 0,
+            Layout::Partitioned { starts, ends } => ends[index] - starts[index] - self.lens[index],
+            Layout::Linked { .. } => self.remaining(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn push(&mut self, element: T, index: usize) -> Result<(), &'static str> {
+        Self::check_index(index)?;
+        let slot = match &mut self.layout {
+            Layout::Partitioned { starts, ends } => {
+                let slot = starts[index] + self.lens[index];
+                if slot == ends[index] {
+                    return Err("stack is full!");
+                }
+                slot
+            }
+            Layout::Linked { next, tops, free } => {
+                // 从空闲链表头部取出一个位置，放到这个栈的栈顶
+                let slot = free.ok_or("stack is full!")?;
+                *free = next[slot];
+                next[slot] = tops[index];
+                tops[index] = Some(slot);
+                slot
+            }
+        };
+        self.data[slot] = Some(element);
+        self.lens[index] += 1;
+        Ok(())
+    }
+
+    pub fn pop(&mut self, index: usize) -> Result<T, &'static str> {
+        let slot = self.top_slot(index)?;
+        if let Layout::Linked { next, tops, free } = &mut self.layout {
+            // 栈顶的位置还给空闲链表
+            tops[index] = next[slot];
+            next[slot] = *free;
+            *free = Some(slot);
+        }
+        self.lens[index] -= 1;
+        Ok(self.data[slot].take().unwrap())
+    }
+
+    pub fn get(&self, index: usize) -> Result<&T, &'static str> {
+        let slot = self.top_slot(index)?;
+        Ok(self.data[slot].as_ref().unwrap())
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Result<&mut T, &'static str> {
+        let slot = self.top_slot(index)?;
+        Ok(self.data[slot].as_mut().unwrap())
+    }
+
+    // 重新划分分区：每个栈保留自己的元素，空闲位置重新平均分配，O(N)
+    // 链式布局中任何栈都可以使用任何空闲位置，不需要重新划分
+    pub fn rebalance(&mut self) {
+        let Layout::Partitioned { starts, ends } = &mut self.layout else {
+            return;
+        };
+        // 先把所有栈依次向左紧凑排列，元素只会向左移动，从左往右处理不会覆盖还没移动的元素
+        let mut compacted = [0; K];
+        let mut start = 0;
+        for i in 0..K {
+            compacted[i] = start;
+            for j in 0..self.lens[i] {
+                self.data.swap(starts[i] + j, start + j);
+            }
+            start += self.lens[i];
+        }
+        Self::partition(&self.lens, starts, ends);
+        // 再把每个栈移动到新分区的开头，元素只会向右移动，所以从右往左处理
+        for i in (0..K).rev() {
+            for j in (0..self.lens[i]).rev() {
+                self.data.swap(compacted[i] + j, starts[i] + j);
+            }
+        }
+    }
+
+    // 某个栈的视图，实现了 Stack trait，不需要每次都传入下标
+    pub fn stack_mut(&mut self, index: usize) -> IndexedStack<'_, T, N, K> {
+        IndexedStack { stack: self, index }
+    }
+}
+
+pub struct IndexedStack<'a, T, const N: usize, const K: usize> {
+    stack: &'a mut MultiStack<T, N, K>,
+    index: usize,
+}
+
+impl<T, const N: usize, const K: usize> Stack<T> for IndexedStack<'_, T, N, K> {
+    fn push(&mut self, element: T) -> Result<(), &'static str> {
+        self.stack.push(element, self.index)
+    }
+
+    fn pop(&mut self) -> Result<T, &'static str> {
+        self.stack.pop(self.index)
+    }
+
+    fn peek(&self) -> Result<&T, &'static str> {
+        self.stack.get(self.index)
+    }
+
+    fn peek_mut(&mut self) -> Result<&mut T, &'static str> {
+        self.stack.get_mut(self.index)
+    }
+
+    fn len(&self) -> usize {
+        self.stack.len(self.index)
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(self.len() + self.stack.available(self.index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linear_structure::test_rng::TestRng;
+
+    #[test]
+    fn test_partitioned() {
+        let mut stacks: MultiStack<i32, 10, 3> = MultiStack::new();
+        assert_eq!(stacks.strategy(), Strategy::Partitioned);
+        // 10 个位置分成 4、3、3
+        assert_eq!((stacks.available(0), stacks.available(1), stacks.available(2)), (4, 3, 3));
+        for i in 0..3 {
+            stacks.push(i, 1).unwrap();
+        }
+        assert_eq!(stacks.push(3, 1), Err("stack is full!"));
+        assert_eq!(stacks.remaining(), 7);
+        stacks.push(100, 2).unwrap();
+        assert_eq!(*stacks.get(2).unwrap(), 100);
+        assert_eq!(stacks.pop(1).unwrap(), 2);
+        assert_eq!(stacks.pop(0), Err("Stack is empty!"));
+        assert_eq!(stacks.push(0, 3), Err("Stack index out of range!"));
+        assert_eq!(stacks.len(3), 0);
+    }
+
+    #[test]
+    fn test_rebalance() {
+        let mut stacks: MultiStack<i32, 9, 3> = MultiStack::new();
+        for i in 0..3 {
+            stacks.push(i, 0).unwrap();
+            stacks.push(10 + i, 2).unwrap();
+        }
+        assert!(stacks.push(3, 0).is_err());
+        stacks.pop(2).unwrap();
+        // 还剩 4 个空闲位置，重新划分之后每个栈至少能再放一个
+        stacks.rebalance();
+        assert_eq!((stacks.available(0), stacks.available(1), stacks.available(2)), (2, 1, 1));
+        stacks.push(3, 0).unwrap();
+        stacks.push(4, 0).unwrap();
+        assert!(stacks.push(5, 0).is_err());
+        assert_eq!(stacks.len(2), 2);
+        for expected in [4, 3, 2, 1, 0] {
+            assert_eq!(stacks.pop(0).unwrap(), expected);
+        }
+        assert_eq!(stacks.pop(2).unwrap(), 11);
+        assert_eq!(stacks.pop(2).unwrap(), 10);
+    }
+
+    #[test]
+    fn test_linked_uses_any_free_slot() {
+        let mut stacks: MultiStack<i32, 6, 3> = MultiStack::with_strategy(Strategy::Linked);
+        for i in 0..5 {
+            stacks.push(i, 2).unwrap();
+        }
+        stacks.push(100, 0).unwrap();
+        assert_eq!(stacks.push(7, 1), Err("stack is full!"));
+        assert_eq!(stacks.pop(2).unwrap(), 4);
+        stacks.push(7, 1).unwrap();
+        *stacks.get_mut(1).unwrap() += 1;
+        assert_eq!(stacks.pop(1).unwrap(), 8);
+        assert_eq!(stacks.available(1), 1);
+        stacks.rebalance();
+        assert_eq!(stacks.pop(2).unwrap(), 3);
+        assert_eq!(stacks.pop(0).unwrap(), 100);
+    }
+
+    #[test]
+    fn test_degenerate_sizes() {
+        for strategy in [Strategy::Partitioned, Strategy::Linked] {
+            let mut empty: MultiStack<i32, 0, 2> = MultiStack::with_strategy(strategy);
+            assert!(empty.push(1, 0).is_err());
+            assert!(empty.pop(1).is_err());
+            let mut none: MultiStack<i32, 4, 0> = MultiStack::with_strategy(strategy);
+            assert_eq!(none.push(1, 0), Err("Stack index out of range!"));
+            none.rebalance();
+            assert_eq!(none.remaining(), 4);
+        }
+    }
+
+    // 随机操作，和 K 个 Vec 组成的模型比较，分区布局在分区满了而数组还有空位时重新划分
+    fn random_operations(strategy: Strategy) {
+        let mut stacks: MultiStack<u32, 16, 4> = MultiStack::with_strategy(strategy);
+        let mut model: [Vec<u32>; 4] = Default::default();
+        let mut rng = TestRng::new(99);
+        for step in 0..20_000 {
+            let seed = rng.next_u32();
+            let index = (seed >> 16) as usize % 4;
+            if (seed >> 20) % 5 < 3 {
+                let mut result = stacks.push(step, index);
+                if result.is_err() && stacks.remaining() > 0 {
+                    stacks.rebalance();
+                    result = stacks.push(step, index);
+                }
+                let total: usize = model.iter().map(Vec::len).sum();
+                if strategy == Strategy::Linked || stacks.remaining() >= 4 {
+                    assert_eq!(result.is_ok(), total < 16);
+                } else if result.is_err() {
+                    // 空闲位置少于 K 个时，重新划分之后不一定每个栈都能分到
+                    assert_eq!(stacks.available(index), 0);
+                }
+                if result.is_ok() {
+                    model[index].push(step);
+                }
+            } else {
+                assert_eq!(stacks.pop(index).ok(), model[index].pop());
+            }
+            for (i, model_stack) in model.iter().enumerate() {
+                assert_eq!(stacks.len(i), model_stack.len());
+                assert_eq!(stacks.get(i).ok(), model_stack.last());
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_operations_match_model() {
+        random_operations(Strategy::Partitioned);
+        random_operations(Strategy::Linked);
+    }
+}
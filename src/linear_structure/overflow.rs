@@ -0,0 +1,87 @@
+// 有界栈（SequentialStack、SqDoubleStack）满了之后再 push 时的处理策略
+
+use super::linked_stack::{self, LinkedStack};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    // 拒绝新元素，这是默认的行为
+    #[default]
+    Reject,
+    // 丢弃最早入栈（栈底）的元素，适合只保留最近 N 条记录的历史缓冲区
+    DropOldest,
+    // 用新元素覆盖栈顶元素
+    OverwriteTop,
+    // 放不下的元素溢出到堆上的链栈中，栈的大小不再受限
+    SpillToHeap,
+}
+
+// 一次 push 实际发生了什么，被丢弃或者覆盖的元素会交还给调用者
+#[derive(Debug, PartialEq, Eq)]
+pub enum PushOutcome<T> {
+    // 栈没满，正常入栈
+    Pushed,
+    Rejected(T),
+    DroppedOldest(T),
+    OverwroteTop(T),
+    Spilled,
+}
+
+impl<T> PushOutcome<T> {
+    // 新元素是否已经在栈中
+    pub fn is_stored(&self) -> bool {
+        !matches!(self, PushOutcome::Rejected(_))
+    }
+}
+
+// SpillToHeap 策略下溢出到堆上的元素
+// 没有元素溢出时只是一个空指针，不占用链栈的空间；最后一个溢出的元素弹出后链栈随即释放
+pub(crate) struct Spill<T>(Option<Box<LinkedStack<T>>>);
+
+impl<T> Spill<T> {
+    pub(crate) const fn new() -> Self {
+        Self(None)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.0.as_ref().map_or(0, |stack| stack.len())
+    }
+
+    pub(crate) fn push(&mut self, element: T) {
+        self.0.get_or_insert_with(Box::default).push(element);
+    }
+
+    pub(crate) fn pop(&mut self) -> Result<T, &'static str> {
+        let stack = self.0.as_mut().ok_or("Stack is empty!")?;
+        let element = stack.pop();
+        if stack.is_empty() {
+            self.0 = None;
+        }
+        element
+    }
+
+    pub(crate) fn pop_bottom(&mut self) -> Result<T, &'static str> {
+        let stack = self.0.as_mut().ok_or("Stack is empty!")?;
+        let element = stack.pop_bottom();
+        if stack.is_empty() {
+            self.0 = None;
+        }
+        element
+    }
+
+    pub(crate) fn peek(&self) -> Result<&T, &'static str> {
+        self.0.as_ref().ok_or("Stack is empty!")?.peek()
+    }
+
+    pub(crate) fn peek_mut(&mut self) -> Result<&mut T, &'static str> {
+        self.0.as_mut().ok_or("Stack is empty!")?.peek_mut()
+    }
+
+    // 从栈顶到栈底遍历
+    pub(crate) fn iter(&self) -> linked_stack::Iter<'_, T> {
+        self.0.as_ref().map_or_else(Default::default, |stack| stack.iter())
+    }
+}
@@ -0,0 +1,286 @@
+// 持久化（不可变）栈：push 和 pop 不修改原来的栈，而是返回一个新的栈，新旧两个栈共享相同的部分
+// 例如 s1 = [b, a]，s2 = s1.push(c) = [c, b, a]，s2 只新建了 c 这一个结点，b 和 a 两个结点由 s1 和 s2 共享
+// 这样保存一个快照只需要 O(1)，很适合回溯搜索：每一层递归保存当时的栈，回溯时直接使用旧的栈即可
+// PersistentStack 使用 Rc 共享结点，ArcPersistentStack 使用 Arc，可以在线程之间传递
+
+use std::{fmt, rc::Rc, sync::Arc};
+
+macro_rules! persistent_stack {
+    ($name:ident, $node:ident, $iter:ident, $ptr:ident) => {
+        struct $node<T> {
+            data: T,
+            next: Option<$ptr<$node<T>>>,
+        }
+
+        // 结点克隆时只增加 next 的引用计数，用于 peek_mut 的写时复制
+        impl<T: Clone> Clone for $node<T> {
+            fn clone(&self) -> Self {
+                Self { data: self.data.clone(), next: self.next.clone() }
+            }
+        }
+
+        pub struct $name<T> {
+            head: Option<$ptr<$node<T>>>,
+            len: usize,
+        }
+
+        impl<T> $name<T> {
+            pub const fn new() -> Self {
+                Self { head: None, len: 0 }
+            }
+
+            pub fn len(&self) -> usize {
+                self.len
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.len == 0
+            }
+
+            // 返回 element 在栈顶的新栈，原来的栈不变，O(1)
+            pub fn push(&self, element: T) -> Self {
+                let node = $node { data: element, next: self.head.clone() };
+                Self { head: Some($ptr::new(node)), len: self.len + 1 }
+            }
+
+            // 返回去掉栈顶之后的新栈，原来的栈不变，O(1)
+            pub fn pop(&self) -> Result<Self, &'static str> {
+                match &self.head {
+                    None => Err("Stack is empty!"),
+                    Some(node) => Ok(Self { head: node.next.clone(), len: self.len - 1 }),
+                }
+            }
+
+            pub fn peek(&self) -> Result<&T, &'static str> {
+                self.head.as_ref().map(|node| &node.data).ok_or("Stack is empty!")
+            }
+
+            // 清空当前这个版本，其他版本不受影响
+            pub fn clear(&mut self) {
+                *self = Self::new();
+            }
+
+            // 从栈顶到栈底遍历
+            pub fn iter(&self) -> $iter<'_, T> {
+                $iter { next: self.head.as_deref(), remaining: self.len }
+            }
+
+            // 两个栈是否共享同一个栈顶结点
+            pub fn ptr_eq(&self, other: &Self) -> bool {
+                match (&self.head, &other.head) {
+                    (Some(a), Some(b)) => $ptr::ptr_eq(a, b),
+                    (None, None) => true,
+                    _ => false,
+                }
+            }
+        }
+
+        impl<T: Clone> $name<T> {
+            // 栈顶结点被其他版本共享时先复制一份（写时复制），所以不会影响其他版本
+            pub fn peek_mut(&mut self) -> Result<&mut T, &'static str> {
+                self.head.as_mut().map(|node| &mut $ptr::make_mut(node).data).ok_or("Stack is empty!")
+            }
+        }
+
+        // 克隆只复制栈顶指针，O(1)
+        impl<T> Clone for $name<T> {
+            fn clone(&self) -> Self {
+                Self { head: self.head.clone(), len: self.len }
+            }
+        }
+
+        impl<T> Default for $name<T> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        // 逐个释放不再被共享的结点，遇到还被其他版本引用的结点就停下，避免递归 drop 很长的链表
+        impl<T> Drop for $name<T> {
+            fn drop(&mut self) {
+                let mut head = self.head.take();
+                while let Some(node) = head {
+                    match $ptr::try_unwrap(node) {
+                        Ok(mut node) => head = node.next.take(),
+                        Err(_) => break,
+                    }
+                }
+            }
+        }
+
+        impl<T: fmt::Debug> fmt::Debug for $name<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_list().entries(self.iter()).finish()
+            }
+        }
+
+        impl<T: PartialEq> PartialEq for $name<T> {
+            fn eq(&self, other: &Self) -> bool {
+                self.len == other.len && self.iter().eq(other.iter())
+            }
+        }
+
+        impl<T: Eq> Eq for $name<T> {}
+
+        // 依次入栈，最后一个元素在栈顶
+        impl<T> FromIterator<T> for $name<T> {
+            fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+                let mut stack = Self::new();
+                stack.extend(iter);
+                stack
+            }
+        }
+
+        impl<T> Extend<T> for $name<T> {
+            fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+                for element in iter {
+                    *self = self.push(element);
+                }
+            }
+        }
+
+        pub struct $iter<'a, T> {
+            next: Option<&'a $node<T>>,
+            remaining: usize,
+        }
+
+        impl<'a, T> Iterator for $iter<'a, T> {
+            type Item = &'a T;
+
+            fn next(&mut self) -> Option<&'a T> {
+                self.next.map(|node| {
+                    self.next = node.next.as_deref();
+                    self.remaining -= 1;
+                    &node.data
+                })
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.remaining, Some(self.remaining))
+            }
+        }
+
+        impl<T> ExactSizeIterator for $iter<'_, T> {}
+
+        impl<'a, T> IntoIterator for &'a $name<T> {
+            type Item = &'a T;
+            type IntoIter = $iter<'a, T>;
+
+            fn into_iter(self) -> $iter<'a, T> {
+                self.iter()
+            }
+        }
+    };
+}
+
+persistent_stack!(PersistentStack, RcNode, Iter, Rc);
+persistent_stack!(ArcPersistentStack, ArcNode, ArcIter, Arc);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_old_versions_stay_valid() {
+        let empty: PersistentStack<i32> = PersistentStack::new();
+        let one = empty.push(1);
+        let two = one.push(2);
+        let three = two.push(3);
+        let other = two.push(30);
+
+        assert!(empty.is_empty());
+        assert_eq!(one.iter().copied().collect::<Vec<_>>(), vec![1]);
+        assert_eq!(two.iter().copied().collect::<Vec<_>>(), vec![2, 1]);
+        assert_eq!(three.iter().copied().collect::<Vec<_>>(), vec![3, 2, 1]);
+        assert_eq!(other.iter().copied().collect::<Vec<_>>(), vec![30, 2, 1]);
+
+        // 两个分支共享 two 的全部结点
+        assert!(three.pop().unwrap().ptr_eq(&two));
+        assert!(other.pop().unwrap().ptr_eq(&two));
+        assert_eq!(three.pop().unwrap(), other.pop().unwrap());
+
+        // 丢弃新版本不影响旧版本
+        drop(three);
+        drop(two);
+        assert_eq!(other.len(), 3);
+        assert_eq!(*other.peek().unwrap(), 30);
+        assert_eq!(one.pop().unwrap().pop(), Err("Stack is empty!"));
+    }
+
+    #[test]
+    fn test_peek_mut_copy_on_write() {
+        let base: PersistentStack<String> = ["a", "b"].into_iter().map(String::from).collect();
+        let mut copy = base.clone();
+        copy.peek_mut().unwrap().push('!');
+        assert_eq!(base.peek().unwrap(), "b");
+        assert_eq!(copy.peek().unwrap(), "b!");
+        // 只复制了栈顶结点，下面的结点仍然共享
+        assert!(base.pop().unwrap().ptr_eq(&copy.pop().unwrap()));
+
+        let mut unique = PersistentStack::new().push(1);
+        *unique.peek_mut().unwrap() += 1;
+        assert_eq!(format!("{:?}", unique), "[2]");
+        unique.clear();
+        assert!(unique.peek_mut().is_err());
+    }
+
+    #[test]
+    fn test_backtracking_snapshots() {
+        // 用持久化栈保存路径，枚举 {1, 2, 3} 的所有子集
+        fn subsets(items: &[i32], path: &PersistentStack<i32>, out: &mut Vec<Vec<i32>>) {
+            match items.split_first() {
+                None => {
+                    let mut subset: Vec<i32> = path.iter().copied().collect();
+                    subset.reverse();
+                    out.push(subset);
+                }
+                Some((&first, rest)) => {
+                    subsets(rest, path, out);
+                    subsets(rest, &path.push(first), out);
+                }
+            }
+        }
+
+        let mut out = Vec::new();
+        subsets(&[1, 2, 3], &PersistentStack::new(), &mut out);
+        out.sort();
+        assert_eq!(out, vec![vec![], vec![1], vec![1, 2], vec![1, 2, 3], vec![1, 3], vec![2], vec![2, 3], vec![3]]);
+    }
+
+    #[test]
+    fn test_long_stack_drop() {
+        let stack: PersistentStack<usize> = (0..1_000_000).collect();
+        let shared = stack.pop().unwrap();
+        drop(stack);
+        assert_eq!(shared.len(), 999_999);
+        drop(shared);
+
+        let stack: ArcPersistentStack<usize> = (0..1_000_000).collect();
+        drop(stack);
+    }
+
+    #[test]
+    fn test_arc_flavor_across_threads() {
+        let base: ArcPersistentStack<u32> = (0..100).collect();
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let base = base.clone();
+                thread::spawn(move || {
+                    let mut stack = base.pop().unwrap();
+                    for i in 0..1000 {
+                        stack = stack.push(t * 1000 + i);
+                    }
+                    stack.iter().map(|&x| x as u64).sum::<u64>()
+                })
+            })
+            .collect();
+        let base_sum: u64 = (0..99).sum();
+        for (t, handle) in handles.into_iter().enumerate() {
+            let pushed: u64 = (0..1000).map(|i| t as u64 * 1000 + i).sum();
+            assert_eq!(handle.join().unwrap(), base_sum + pushed);
+        }
+        assert_eq!(base.len(), 100);
+        assert_eq!(*base.peek().unwrap(), 99);
+    }
+}
@@ -0,0 +1,136 @@
+// 链式结构共用的结点池
+// 释放结点时不把内存还给分配器，而是留在空闲列表中，下一次分配结点时直接复用
+// 容量为 0 时不保留任何结点，行为和直接使用 Box 完全相同，也不占用额外的堆内存，所以结点池是可选的
+
+use std::{mem::MaybeUninit, ptr};
+
+// 结点池的统计信息
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PoolStats {
+    // 向分配器申请新结点的次数
+    pub allocations: usize,
+    // 从空闲列表中复用结点的次数
+    pub reuses: usize,
+    // 当前空闲列表中的结点个数
+    pub pooled: usize,
+    // 空闲列表最多保留多少个结点
+    pub capacity: usize,
+}
+
+// 容量为 0 时只有两个字长，不分配任何东西；第一次用到结点池时才在堆上创建空闲列表和统计信息
+pub(crate) struct NodePool<N> {
+    capacity: usize,
+    state: Option<Box<PoolState<N>>>,
+}
+
+struct PoolState<N> {
+    free: Vec<Box<MaybeUninit<N>>>,
+    allocations: usize,
+    reuses: usize,
+}
+
+impl<N> NodePool<N> {
+    pub(crate) const fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub(crate) const fn with_capacity(capacity: usize) -> Self {
+        Self { capacity, state: None }
+    }
+
+    // 是否会保留结点，为 false 时 alloc 和 recycle 就是普通的 Box::new 和解引用
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    fn state(&mut self) -> &mut PoolState<N> {
+        self.state.get_or_insert_with(|| Box::new(PoolState { free: Vec::new(), allocations: 0, reuses: 0 }))
+    }
+
+    // 优先复用空闲结点
+    pub(crate) fn alloc(&mut self, node: N) -> Box<N> {
+        if !self.is_enabled() {
+            return Box::new(node);
+        }
+        let state = self.state();
+        match state.free.pop() {
+            Some(slot) => {
+                state.reuses += 1;
+                Box::write(slot, node)
+            }
+            None => {
+                state.allocations += 1;
+                Box::new(node)
+            }
+        }
+    }
+
+    // 取出结点中的值，空闲列表没满就把结点的内存留下来
+    pub(crate) fn recycle(&mut self, node: Box<N>) -> N {
+        let capacity = self.capacity;
+        if capacity == 0 {
+            return *node;
+        }
+        let state = self.state();
+        if state.free.len() >= capacity {
+            return *node;
+        }
+        let raw = Box::into_raw(node);
+        // 值被移出之后，这块内存就是未初始化的，用 MaybeUninit 保存，释放时不会再 drop 一次
+        let value = unsafe { ptr::read(raw) };
+        state.free.push(unsafe { Box::from_raw(raw.cast::<MaybeUninit<N>>()) });
+        value
+    }
+
+    // 把空闲结点全部还给分配器
+    pub(crate) fn shrink(&mut self) {
+        if let Some(state) = &mut self.state {
+            state.free = Vec::new();
+        }
+    }
+
+    // 没有启用结点池时不做统计，除了 capacity 都是 0
+    pub(crate) fn stats(&self) -> PoolStats {
+        match &self.state {
+            Some(state) => PoolStats { allocations: state.allocations, reuses: state.reuses, pooled: state.free.len(), capacity: self.capacity },
+            None => PoolStats { capacity: self.capacity, ..PoolStats::default() },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recycle_and_reuse() {
+        let mut pool: NodePool<String> = NodePool::with_capacity(2);
+        let a = pool.alloc("a".to_string());
+        let b = pool.alloc("b".to_string());
+        let c = pool.alloc("c".to_string());
+        assert_eq!(pool.recycle(a), "a");
+        assert_eq!(pool.recycle(b), "b");
+        // 空闲列表已满，这个结点直接释放
+        assert_eq!(pool.recycle(c), "c");
+        assert_eq!(pool.stats(), PoolStats { allocations: 3, reuses: 0, pooled: 2, capacity: 2 });
+
+        let d = pool.alloc("d".to_string());
+        assert_eq!(*d, "d");
+        assert_eq!(pool.stats().reuses, 1);
+        drop(d);
+        pool.shrink();
+        assert_eq!(pool.stats().pooled, 0);
+    }
+
+    #[test]
+    fn test_zero_capacity_is_plain_boxing() {
+        let mut pool: NodePool<Vec<i32>> = NodePool::new();
+        for i in 0..10 {
+            let node = pool.alloc(vec![i]);
+            assert_eq!(pool.recycle(node), vec![i]);
+        }
+        assert_eq!(pool.stats(), PoolStats::default());
+        assert!(pool.state.is_none());
+        assert_eq!(std::mem::size_of::<NodePool<Vec<i32>>>(), 2 * std::mem::size_of::<usize>());
+    }
+}
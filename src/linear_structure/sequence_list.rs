@@ -20,7 +20,16 @@
     let size = 10;
     let list: SeqList<i32, size> = SeqList::new(); // 编译错误
 */
-#[derive(Debug)]
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    mem::ManuallyDrop,
+    ops::{Bound, RangeBounds},
+    ptr,
+};
+
+#[derive(Debug, Clone)]
 pub struct SeqList<T: std::fmt::Debug, const N: usize> {
     data: [Option<T>; N],
     len: usize,
@@ -35,9 +44,15 @@ pub struct SeqList<T: std::fmt::Debug, const N: usize> {
     // ❌ 编译错误：不同类型不能直接赋值
     list1 = list2; // Error!
 */
+impl<T: std::fmt::Debug, const N: usize> Default for SeqList<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T: std::fmt::Debug, const N: usize> SeqList<T, N> {
-    // 构造方法
-    pub fn new() -> Self {
+    // 构造方法，const fn 使得 SeqList 可以直接放进 static/const 中，例如 static LIST: Mutex<SeqList<i32, 8>> = Mutex::new(SeqList::new());
+    pub const fn new() -> Self {
         Self {
             // const {None}是一个常量表达式，创建一个Option<T>类型的None值
             // 类似的我们还可以创建长度为N，元素都为0的元素，Rust 要求数组重复初始化语法 [value; N] 中的 value 必须是常量表达式const {None} 明确地标记这是一个常量表达式
@@ -47,19 +62,37 @@ impl<T: std::fmt::Debug, const N: usize> SeqList<T, N> {
         }
     }
 
-    pub fn is_empty(&self) -> bool {
+    // 用一个长度为 M 的数组构造顺序表，M <= N 在编译期检查，M > N 时直接编译失败
+    pub const fn from_array<const M: usize>(array: [T; M]) -> Self {
+        const { assert!(M <= N, "SeqList::from_array: array is longer than the capacity") };
+        // 数组中的元素会被逐个移动到 data 中，用 ManuallyDrop 防止原数组再次 drop 这些元素
+        let array = ManuallyDrop::new(array);
+        let src = (&raw const array).cast::<T>();
+        let mut data = [const { None }; N];
+        let mut i = 0;
+        // const fn 中不能使用 for 循环，也不能对可能需要 drop 的位置直接赋值，所以使用 while + ptr::write
+        while i < M {
+            unsafe {
+                ptr::write(&mut data[i], Some(ptr::read(src.add(i))));
+            }
+            i += 1;
+        }
+        Self { data, len: M }
+    }
+
+    pub const fn is_empty(&self) -> bool {
         self.len == 0
     }
 
-    pub fn is_full(&self) -> bool {
+    pub const fn is_full(&self) -> bool {
         self.len == N
     }
 
-    pub fn len(&self) -> usize {
+    pub const fn len(&self) -> usize {
         self.len
     }
 
-    pub fn capacity(&self) -> usize {
+    pub const fn capacity(&self) -> usize {
         N
     }
 
@@ -83,18 +116,54 @@ impl<T: std::fmt::Debug, const N: usize> SeqList<T, N> {
         } else if pos > self.len + 1 || pos == 0 {
             Err("Out of List!")
         } else {
-            // for循环也可以写，rev()方法可以翻转迭代顺序
-            for i in ((pos - 1)..=(self.len - 1)).rev() {
-                // 这样直接移动元素是不行的，没有实现Copy trait，应该使用更加安全的方法，获取元素并存放，这里使用的是take()方法
-                // self.data[i+1] = self.data[i];
-                self.data[i + 1] = self.data[i].take();
-            }
-            self.data[pos - 1] = Some(element);
+            // 先把元素放在末尾，再把 [pos-1, len] 这一段循环右移一位，每个元素只移动一次
+            // 这样直接移动元素是不行的，没有实现Copy trait：self.data[i+1] = self.data[i]; rotate_right 在切片内部交换位置，不需要 Copy
+            self.data[self.len] = Some(element);
+            self.data[(pos - 1)..=self.len].rotate_right(1);
             self.len += 1;
             Ok(())
         }
     }
 
+    // 在 pos 位置（从 1 开始）插入迭代器中的所有元素，容量不够时返回错误，并且顺序表保持不变
+    pub fn insert_many<I: IntoIterator<Item = T>>(&mut self, pos: usize, iter: I) -> Result<(), &'static str> {
+        if pos > self.len + 1 || pos == 0 {
+            return Err("Out of List!");
+        }
+        // 迭代器的长度事先未知，先把元素依次放到末尾的空闲位置上
+        let mut end = self.len;
+        for element in iter {
+            if end == N {
+                // 容量不够，丢弃已经放进来的元素，恢复原状
+                for slot in &mut self.data[self.len..end] {
+                    slot.take();
+                }
+                return Err("List is full!");
+            }
+            self.data[end] = Some(element);
+            end += 1;
+        }
+        let count = end - self.len;
+        self.data[(pos - 1)..end].rotate_right(count);
+        self.len = end;
+        Ok(())
+    }
+
+    // 在末尾追加切片中的所有元素，容量不够时一个也不追加
+    pub fn extend_from_slice(&mut self, elements: &[T]) -> Result<(), &'static str>
+    where
+        T: Clone,
+    {
+        if elements.len() > N - self.len {
+            return Err("List is full!");
+        }
+        for (slot, element) in self.data[self.len..].iter_mut().zip(elements) {
+            *slot = Some(element.clone());
+        }
+        self.len += elements.len();
+        Ok(())
+    }
+
     pub fn get(&mut self, pos: usize) -> Option<&T> {
         if pos > self.len || pos < 1 {
             None
@@ -104,21 +173,682 @@ impl<T: std::fmt::Debug, const N: usize> SeqList<T, N> {
     }
 
     pub fn remove(&mut self, pos: usize) -> Result<Option<T>, &'static str> {
-        if pos < 1 && pos >= self.len {
+        if self.is_empty() {
+            Err("List is empty")
+        } else if pos < 1 || pos > self.len {
             Err("Out of List")
-        } else if self.is_empty() {
+        } else {
+            // 与 insert 相反，把 [pos-1, len) 循环左移一位，被删除的元素落到末尾再取出
+            self.data[(pos - 1)..self.len].rotate_left(1);
+            self.len -= 1;
+            Ok(self.data[self.len].take())
+        }
+    }
+
+    // 删除 pos 位置的元素，并用最后一个元素填补空位，O(1) 但是不保持顺序
+    pub fn swap_remove(&mut self, pos: usize) -> Result<T, &'static str> {
+        if self.is_empty() {
             Err("List is empty")
+        } else if pos < 1 || pos > self.len {
+            Err("Out of List")
         } else {
-            let removed = self.data[pos - 1].take();
-            for i in pos..=(self.len - 1) {
-                self.data[i - 1] = self.data[i].take();
+            self.data.swap(pos - 1, self.len - 1);
+            self.len -= 1;
+            Ok(self.data[self.len].take().unwrap())
+        }
+    }
+
+    // 按下标范围（和切片一样从 0 开始，左闭右开）移除元素，返回的迭代器会依次交出被移除的元素
+    // 迭代器被 drop 时才会把后面的元素整体前移，所以每个元素最多只移动一次
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Result<Drain<'_, T, N>, &'static str> {
+        let start = match range.start_bound() {
+            Bound::Included(&i) => i,
+            Bound::Excluded(&i) => i.checked_add(1).ok_or("Out of List!")?,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&i) => i.checked_add(1).ok_or("Out of List!")?,
+            Bound::Excluded(&i) => i,
+            Bound::Unbounded => self.len,
+        };
+        if start > end || end > self.len {
+            return Err("Out of List!");
+        }
+        let old_len = self.len;
+        // 先把长度缩短到 start，即使 Drain 被 mem::forget，顺序表也只是丢失了后面的元素
+        self.len = start;
+        Ok(Drain { list: self, front: start, back: end, tail: end, old_len })
+    }
+
+    // 只保留满足条件的元素，一次遍历完成，每个保留下来的元素最多移动一次
+    // f panic 时由 guard 把还没有检查的元素前移，顺序表仍然是连续的，正在检查的元素也会保留
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        struct RetainGuard<'a, T: std::fmt::Debug, const N: usize> {
+            list: &'a mut SeqList<T, N>,
+            // [0, kept) 是保留下来的元素，[checked, old_len) 是还没有检查的元素
+            kept: usize,
+            checked: usize,
+            old_len: usize,
+        }
+
+        // 和 Drain 一样，在 drop 时把后半段前移并修正长度
+        impl<T: std::fmt::Debug, const N: usize> Drop for RetainGuard<'_, T, N> {
+            fn drop(&mut self) {
+                for i in self.checked..self.old_len {
+                    self.list.data[self.kept + i - self.checked] = self.list.data[i].take();
+                }
+                self.list.len = self.kept + self.old_len - self.checked;
             }
+        }
+
+        let old_len = self.len;
+        let mut guard = RetainGuard { list: self, kept: 0, checked: 0, old_len };
+        while guard.checked < old_len {
+            let i = guard.checked;
+            if f(guard.list.data[i].as_ref().unwrap()) {
+                if i != guard.kept {
+                    guard.list.data[guard.kept] = guard.list.data[i].take();
+                }
+                guard.kept += 1;
+                guard.checked += 1;
+            } else {
+                // 先记录进度再释放元素，元素的 drop panic 时也不会留下空位
+                let removed = guard.list.data[i].take();
+                guard.checked += 1;
+                drop(removed);
+            }
+        }
+    }
+
+    // 删除连续重复的元素，只保留第一个
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        if self.len <= 1 {
+            return;
+        }
+        let mut kept = 1;
+        for i in 1..self.len {
+            if self.data[i] == self.data[kept - 1] {
+                self.data[i] = None;
+            } else {
+                if i != kept {
+                    self.data[kept] = self.data[i].take();
+                }
+                kept += 1;
+            }
+        }
+        self.len = kept;
+    }
+
+    // 只保留前 len 个元素，len 大于当前长度时什么也不做
+    pub fn truncate(&mut self, len: usize) {
+        while self.len > len {
             self.len -= 1;
-            Ok(removed)
+            self.data[self.len] = None;
         }
     }
 
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    pub fn reverse(&mut self) {
+        self.data[..self.len].reverse();
+    }
+
+    // 循环左移 k 位，k 大于长度时按 k % len 处理
+    pub fn rotate_left(&mut self, k: usize) {
+        if self.len > 0 {
+            self.data[..self.len].rotate_left(k % self.len);
+        }
+    }
+
+    // 循环右移 k 位，k 大于长度时按 k % len 处理
+    pub fn rotate_right(&mut self, k: usize) {
+        if self.len > 0 {
+            self.data[..self.len].rotate_right(k % self.len);
+        }
+    }
+
+    // 从迭代器构造顺序表，放不下的元素会通过 CapacityError 交还给调用者
+    pub fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, CapacityError<T, N>> {
+        let mut list = Self::new();
+        let mut iter = iter.into_iter();
+        for element in iter.by_ref() {
+            if let Err(element) = list.push_or_return(element) {
+                let mut rest = vec![element];
+                rest.extend(iter);
+                return Err(CapacityError { list, rest });
+            }
+        }
+        Ok(list)
+    }
+
+    fn push_or_return(&mut self, element: T) -> Result<(), T> {
+        if self.is_full() {
+            Err(element)
+        } else {
+            self.data[self.len] = Some(element);
+            self.len += 1;
+            Ok(())
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { inner: self.data[..self.len].iter() }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut { inner: self.data[..self.len].iter_mut() }
+    }
+
     pub fn print(&self) {
         println!("{:?}", self.data);
     }
 }
+
+// 容量不足时的错误，list 是已经放进去的部分，rest 是放不下的元素
+#[derive(Debug)]
+pub struct CapacityError<T: std::fmt::Debug, const N: usize> {
+    pub list: SeqList<T, N>,
+    pub rest: Vec<T>,
+}
+
+impl<T: std::fmt::Debug, const N: usize> CapacityError<T, N> {
+    // 溢出的元素个数
+    pub fn overflow(&self) -> usize {
+        self.rest.len()
+    }
+
+    pub fn into_parts(self) -> (SeqList<T, N>, Vec<T>) {
+        (self.list, self.rest)
+    }
+}
+
+impl<T: std::fmt::Debug, const N: usize> fmt::Display for CapacityError<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "List is full! {} element(s) exceed the capacity {}", self.rest.len(), N)
+    }
+}
+
+impl<T: std::fmt::Debug, const N: usize> std::error::Error for CapacityError<T, N> {}
+
+impl<T: std::fmt::Debug, const N: usize> TryFrom<Vec<T>> for SeqList<T, N> {
+    type Error = CapacityError<T, N>;
+
+    fn try_from(vec: Vec<T>) -> Result<Self, Self::Error> {
+        Self::try_from_iter(vec)
+    }
+}
+
+// 长度在编译期已知时可以使用 from_array 得到编译期检查，这里是运行时检查的版本
+impl<T: std::fmt::Debug, const N: usize, const M: usize> TryFrom<[T; M]> for SeqList<T, N> {
+    type Error = CapacityError<T, N>;
+
+    fn try_from(array: [T; M]) -> Result<Self, Self::Error> {
+        Self::try_from_iter(array)
+    }
+}
+
+// 比较、哈希只考虑有效元素，和切片的行为保持一致，不同容量的顺序表之间也可以比较
+impl<T: std::fmt::Debug + PartialEq, const N: usize, const M: usize> PartialEq<SeqList<T, M>> for SeqList<T, N> {
+    fn eq(&self, other: &SeqList<T, M>) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: std::fmt::Debug + Eq, const N: usize> Eq for SeqList<T, N> {}
+
+impl<T: std::fmt::Debug + PartialOrd, const N: usize, const M: usize> PartialOrd<SeqList<T, M>> for SeqList<T, N> {
+    fn partial_cmp(&self, other: &SeqList<T, M>) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: std::fmt::Debug + Ord, const N: usize> Ord for SeqList<T, N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: std::fmt::Debug + Hash, const N: usize> Hash for SeqList<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len);
+        for element in self.iter() {
+            element.hash(state);
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    inner: std::slice::Iter<'a, Option<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next().map(|slot| slot.as_ref().unwrap())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|slot| slot.as_ref().unwrap())
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IterMut<'a, T> {
+    inner: std::slice::IterMut<'a, Option<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        self.inner.next().map(|slot| slot.as_mut().unwrap())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|slot| slot.as_mut().unwrap())
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+// 按值遍历，[front, back) 是还没有交出去的元素
+pub struct IntoIter<T: std::fmt::Debug, const N: usize> {
+    list: SeqList<T, N>,
+    front: usize,
+    back: usize,
+}
+
+impl<T: std::fmt::Debug, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.front == self.back {
+            None
+        } else {
+            self.front += 1;
+            self.list.data[self.front - 1].take()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T: std::fmt::Debug, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.front == self.back {
+            None
+        } else {
+            self.back -= 1;
+            self.list.data[self.back].take()
+        }
+    }
+}
+
+impl<T: std::fmt::Debug, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T: std::fmt::Debug, const N: usize> IntoIterator for SeqList<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> IntoIter<T, N> {
+        let back = self.len;
+        IntoIter { list: self, front: 0, back }
+    }
+}
+
+impl<'a, T: std::fmt::Debug, const N: usize> IntoIterator for &'a SeqList<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T: std::fmt::Debug, const N: usize> IntoIterator for &'a mut SeqList<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+// SeqList::drain 返回的迭代器
+pub struct Drain<'a, T: std::fmt::Debug, const N: usize> {
+    list: &'a mut SeqList<T, N>,
+    // [front, back) 是还没有交出去的元素
+    front: usize,
+    back: usize,
+    // [tail, old_len) 是被移除范围之后需要前移的元素
+    tail: usize,
+    old_len: usize,
+}
+
+impl<T: std::fmt::Debug, const N: usize> Iterator for Drain<'_, T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.front == self.back {
+            None
+        } else {
+            self.front += 1;
+            self.list.data[self.front - 1].take()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T: std::fmt::Debug, const N: usize> DoubleEndedIterator for Drain<'_, T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.front == self.back {
+            None
+        } else {
+            self.back -= 1;
+            self.list.data[self.back].take()
+        }
+    }
+}
+
+impl<T: std::fmt::Debug, const N: usize> ExactSizeIterator for Drain<'_, T, N> {}
+
+impl<T: std::fmt::Debug, const N: usize> Drop for Drain<'_, T, N> {
+    fn drop(&mut self) {
+        // 没有被取走的元素直接丢弃
+        for slot in &mut self.list.data[self.front..self.back] {
+            slot.take();
+        }
+        // 把后半段整体前移，填补被移除的空位
+        let start = self.list.len;
+        for i in self.tail..self.old_len {
+            self.list.data[start + i - self.tail] = self.list.data[i].take();
+        }
+        self.list.len = start + self.old_len - self.tail;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    const EMPTY: SeqList<i32, 4> = SeqList::new();
+    const TABLE: SeqList<&str, 4> = SeqList::from_array(["a", "b", "c"]);
+    static SHARED: Mutex<SeqList<String, 8>> = Mutex::new(SeqList::new());
+
+    #[test]
+    fn test_const_new() {
+        const LEN: usize = EMPTY.len();
+        const CAPACITY: usize = EMPTY.capacity();
+        assert_eq!(LEN, 0);
+        assert_eq!(CAPACITY, 4);
+        assert!(EMPTY.is_empty());
+        assert!(!EMPTY.is_full());
+    }
+
+    #[test]
+    fn test_const_from_array() {
+        let mut list = TABLE;
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.get(1), Some(&"a"));
+        assert_eq!(list.get(3), Some(&"c"));
+        assert_eq!(list.get(4), None);
+
+        let mut full: SeqList<i32, 2> = SeqList::from_array([1, 2]);
+        assert!(full.is_full());
+        assert!(full.push(3).is_err());
+        assert_eq!(full.get(2), Some(&2));
+
+        let empty: SeqList<i32, 0> = SeqList::from_array([]);
+        assert!(empty.is_empty());
+        assert!(empty.is_full());
+    }
+
+    #[test]
+    fn test_from_array_moves_owned_elements() {
+        let list: SeqList<String, 3> = SeqList::from_array(["x".to_string(), "y".to_string()]);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.data[0].as_deref(), Some("x"));
+        assert_eq!(list.data[1].as_deref(), Some("y"));
+        assert!(list.data[2].is_none());
+    }
+
+    #[test]
+    fn test_static_mutex() {
+        let mut list = SHARED.lock().unwrap();
+        list.push("hello".to_string()).unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.get(1).map(String::as_str), Some("hello"));
+    }
+
+    fn to_vec<T: std::fmt::Debug + Clone, const N: usize>(list: &SeqList<T, N>) -> Vec<T> {
+        list.data[..list.len].iter().map(|x| x.clone().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut list: SeqList<i32, 4> = SeqList::new();
+        list.insert(2, 1).unwrap();
+        list.insert(1, 1).unwrap();
+        list.insert(4, 3).unwrap();
+        list.insert(3, 3).unwrap();
+        assert_eq!(to_vec(&list), vec![1, 2, 3, 4]);
+        assert!(list.insert(5, 1).is_err());
+
+        assert_eq!(list.remove(0), Err("Out of List"));
+        assert_eq!(list.remove(5), Err("Out of List"));
+        assert_eq!(list.remove(2), Ok(Some(2)));
+        assert_eq!(list.remove(3), Ok(Some(4)));
+        assert_eq!(to_vec(&list), vec![1, 3]);
+        assert!(list.data[2].is_none());
+    }
+
+    #[test]
+    fn test_extend_from_slice() {
+        let mut list: SeqList<i32, 5> = SeqList::new();
+        list.extend_from_slice(&[1, 2, 3]).unwrap();
+        assert_eq!(list.extend_from_slice(&[4, 5, 6]), Err("List is full!"));
+        assert_eq!(to_vec(&list), vec![1, 2, 3]);
+        list.extend_from_slice(&[4, 5]).unwrap();
+        assert!(list.is_full());
+        assert_eq!(to_vec(&list), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_insert_many() {
+        let mut list: SeqList<i32, 6> = SeqList::from_array([1, 5]);
+        list.insert_many(2, [2, 3, 4]).unwrap();
+        assert_eq!(to_vec(&list), vec![1, 2, 3, 4, 5]);
+        list.insert_many(6, std::iter::empty()).unwrap();
+        assert_eq!(list.insert_many(0, [0]), Err("Out of List!"));
+        assert_eq!(list.insert_many(1, [7, 8]), Err("List is full!"));
+        assert_eq!(to_vec(&list), vec![1, 2, 3, 4, 5]);
+        assert!(list.data[5].is_none());
+        list.insert_many(6, [6]).unwrap();
+        assert_eq!(to_vec(&list), vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut list: SeqList<i32, 6> = SeqList::from_array([1, 2, 3, 4, 5, 6]);
+        let drained: Vec<i32> = list.drain(1..4).unwrap().collect();
+        assert_eq!(drained, vec![2, 3, 4]);
+        assert_eq!(to_vec(&list), vec![1, 5, 6]);
+
+        // 只取走一部分，剩下的在 drop 时被丢弃
+        let mut drain = list.drain(..).unwrap();
+        assert_eq!(drain.next_back(), Some(6));
+        drop(drain);
+        assert!(list.is_empty());
+
+        let mut list: SeqList<i32, 4> = SeqList::from_array([1, 2, 3]);
+        assert!(list.drain(2..5).is_err());
+        assert_eq!(list.drain(1..=1).unwrap().len(), 1);
+        assert_eq!(to_vec(&list), vec![1, 3]);
+
+        // 边界加一时溢出也当作越界，而不是 panic
+        assert!(list.drain(..=usize::MAX).is_err());
+        assert!(list.drain((Bound::Excluded(usize::MAX), Bound::Unbounded)).is_err());
+        assert_eq!(to_vec(&list), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_retain_and_dedup() {
+        let mut list: SeqList<i32, 8> = SeqList::from_array([1, 1, 2, 3, 3, 3, 4, 1]);
+        list.dedup();
+        assert_eq!(to_vec(&list), vec![1, 2, 3, 4, 1]);
+        list.retain(|x| x % 2 == 1);
+        assert_eq!(to_vec(&list), vec![1, 3, 1]);
+        assert!(list.data[3..].iter().all(Option::is_none));
+        list.retain(|_| false);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_retain_panic_keeps_list_contiguous() {
+        let mut list: SeqList<i32, 8> = SeqList::from_array([1, 2, 3, 4, 5, 6]);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            list.retain(|&x| {
+                assert!(x != 4, "stop at 4");
+                x % 2 == 1
+            })
+        }));
+        assert!(result.is_err());
+        // 1、3 被保留，2 被移除，4 以及之后还没有检查的元素原样保留
+        assert_eq!(to_vec(&list), vec![1, 3, 4, 5, 6]);
+        assert!(list.data[5..].iter().all(Option::is_none));
+        list.retain(|&x| x > 3);
+        assert_eq!(to_vec(&list), vec![4, 5, 6]);
+    }
+
+    #[test]
+    fn test_swap_remove_truncate_clear() {
+        let mut list: SeqList<String, 4> = SeqList::new();
+        list.extend_from_slice(&["a".to_string(), "b".to_string(), "c".to_string()]).unwrap();
+        assert_eq!(list.swap_remove(1).unwrap(), "a");
+        assert_eq!(to_vec(&list), vec!["c", "b"]);
+        assert!(list.swap_remove(3).is_err());
+
+        list.truncate(5);
+        assert_eq!(list.len(), 2);
+        list.truncate(1);
+        assert_eq!(to_vec(&list), vec!["c"]);
+        list.clear();
+        assert!(list.is_empty());
+        assert!(list.swap_remove(1).is_err());
+    }
+
+    #[test]
+    fn test_reverse_and_rotate() {
+        let mut list: SeqList<i32, 6> = SeqList::from_array([1, 2, 3, 4, 5]);
+        list.reverse();
+        assert_eq!(to_vec(&list), vec![5, 4, 3, 2, 1]);
+        list.rotate_left(2);
+        assert_eq!(to_vec(&list), vec![3, 2, 1, 5, 4]);
+        list.rotate_right(7);
+        assert_eq!(to_vec(&list), vec![5, 4, 3, 2, 1]);
+
+        let mut empty: SeqList<i32, 2> = SeqList::new();
+        empty.rotate_left(3);
+        empty.reverse();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_clone_eq_ord_hash() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash_of<H: Hash>(value: &H) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let list: SeqList<i32, 4> = SeqList::from_array([1, 2, 3]);
+        let mut copy = list.clone();
+        assert_eq!(list, copy);
+        assert_eq!(hash_of(&list), hash_of(&copy));
+
+        // 被删除过的位置不影响比较
+        copy.push(4).unwrap();
+        copy.truncate(3);
+        assert_eq!(list, copy);
+        assert_eq!(hash_of(&list), hash_of(&copy));
+
+        let bigger: SeqList<i32, 8> = SeqList::from_array([1, 2, 3]);
+        assert!(list == bigger);
+
+        let shorter: SeqList<i32, 4> = SeqList::from_array([1, 2]);
+        let larger: SeqList<i32, 4> = SeqList::from_array([1, 3]);
+        assert!(shorter < list);
+        assert!(list < larger);
+        assert_eq!(list.cmp(&list.clone()), Ordering::Equal);
+        assert_eq!(SeqList::<i32, 4>::default(), SeqList::<i32, 4>::new());
+    }
+
+    #[test]
+    fn test_into_iterator() {
+        let mut list: SeqList<String, 4> = SeqList::from_array(["a".to_string(), "b".to_string(), "c".to_string()]);
+        for element in &mut list {
+            element.push('!');
+        }
+        let borrowed: Vec<&str> = (&list).into_iter().map(String::as_str).collect();
+        assert_eq!(borrowed, vec!["a!", "b!", "c!"]);
+        assert_eq!(list.iter().next_back().unwrap(), "c!");
+
+        let mut owned = list.into_iter();
+        assert_eq!(owned.len(), 3);
+        assert_eq!(owned.next_back().unwrap(), "c!");
+        assert_eq!(owned.collect::<Vec<_>>(), vec!["a!", "b!"]);
+    }
+
+    #[test]
+    fn test_try_from_conversions() {
+        let list = SeqList::<i32, 4>::try_from(vec![1, 2, 3]).unwrap();
+        assert_eq!(list, SeqList::<i32, 3>::from_array([1, 2, 3]));
+
+        let err = SeqList::<i32, 2>::try_from(vec![1, 2, 3, 4]).unwrap_err();
+        assert_eq!(err.overflow(), 2);
+        assert_eq!(err.to_string(), "List is full! 2 element(s) exceed the capacity 2");
+        let (filled, rest) = err.into_parts();
+        assert_eq!(filled, SeqList::<i32, 2>::from_array([1, 2]));
+        assert_eq!(rest, vec![3, 4]);
+
+        let list = SeqList::<i32, 3>::try_from([7, 8, 9]).unwrap();
+        assert!(list.is_full());
+        let err = SeqList::<i32, 1>::try_from([7, 8, 9]).unwrap_err();
+        assert_eq!(err.rest, vec![8, 9]);
+
+        let list = SeqList::<u32, 8>::try_from_iter(1..=5).unwrap();
+        assert_eq!(list.iter().sum::<u32>(), 15);
+        let err = SeqList::<u32, 3>::try_from_iter(1..=5).unwrap_err();
+        assert_eq!(err.list.len(), 3);
+        assert_eq!(err.rest, vec![4, 5]);
+    }
+}
@@ -7,91 +7,623 @@
  * @Description: 这是默认设置,请设置`customMade`, 打开koroFileHeader查看配置 进行设置: https://github.com/OBKoro1/koro1FileHeader/wiki/%E9%85%8D%E7%BD%AE
  */
 
+use std::{cell::Cell, marker::PhantomData, ptr::NonNull, slice};
+
+use super::{
+    linked_stack,
+    overflow::{OverflowPolicy, PushOutcome, Spill},
+    stack::Stack,
+};
+
+// 左栈从 data[0] 开始向右增长，右栈从 data[N-1] 开始向左增长，两侧共享中间的空闲空间
+// 每一侧的元素个数单独记录，N 个位置都可以使用，两侧合计放满 N 个元素时才算满
 pub struct SqDoubleStack<T, const N: usize> {
     data: [Option<T>; N],
-    top0: usize,
-    top1: usize
+    // 两侧在数组中的元素个数，不包括溢出到堆上的元素
+    lens: [usize; 2],
+    policy: OverflowPolicy,
+    // SpillToHeap 策略下两侧各自溢出到堆上的元素，位于数组中该侧所有元素的上方
+    spill: [Spill<T>; 2],
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StackNum {
     First = 0,
     Second = 1,
 }
 
+impl StackNum {
+    fn empty_error(self) -> &'static str {
+        match self {
+            StackNum::First => "Left stack is empty!",
+            StackNum::Second => "Right stack is empty!",
+        }
+    }
+}
+
+impl<T, const N: usize> Default for SqDoubleStack<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T, const N: usize> SqDoubleStack<T, N> {
     pub fn new() -> Self {
+        Self::with_policy(OverflowPolicy::Reject)
+    }
+
+    pub fn with_policy(policy: OverflowPolicy) -> Self {
         Self {
             data: [const {None}; N],
-            top0: 0,
-            top1: N-1,
+            lens: [0, 0],
+            policy,
+            spill: [Spill::new(), Spill::new()],
+        }
+    }
+
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    // 任意一侧还有元素溢出在堆上时只能保持 SpillToHeap，和 SequentialStack 一样
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy) -> Result<(), &'static str> {
+        if self.spill.iter().any(|spill| !spill.is_empty()) && policy != OverflowPolicy::SpillToHeap {
+            return Err("Spilled elements must be popped first!");
+        }
+        self.policy = policy;
+        Ok(())
+    }
+
+    // 某一侧的元素个数，包括溢出到堆上的元素
+    pub fn len(&self, stack_num: StackNum) -> usize {
+        self.lens[stack_num as usize] + self.spill[stack_num as usize].len()
+    }
+
+    pub fn is_empty(&self, stack_num: StackNum) -> bool {
+        self.len(stack_num) == 0
+    }
+
+    // 数组中还剩多少空闲位置，两侧都可以使用
+    pub fn remaining(&self) -> usize {
+        N - self.lens[0] - self.lens[1]
+    }
+
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    // 某一侧栈顶元素在 data 中的下标，这一侧在数组中必须有元素
+    fn top_slot(&self, stack_num: StackNum) -> usize {
+        match stack_num {
+            StackNum::First => self.lens[0] - 1,
+            StackNum::Second => N - self.lens[1],
         }
     }
 
+    // 满了之后按照当前的溢出策略处理，默认的 Reject 策略返回 "stack is full!"
     pub fn push(&mut self, element: T, stack_num: StackNum) -> Result<(), &'static str>{
-        if self.top0 + 1 == self.top1 {
-            Err("stack is full!")
-        } else {
-            match stack_num {
-                StackNum::First => {
-                    self.top0+=1;
-                    self.data[self.top0] = Some(element);
-                }
-                StackNum::Second => {
-                    self.top1-=1;
-                    self.data[self.top1] = Some(element);
-                }
-            }
-            Ok(())
+        match self.push_with_outcome(element, stack_num) {
+            PushOutcome::Rejected(_) => Err("stack is full!"),
+            _ => Ok(()),
+        }
+    }
+
+    // 和push相同，但是会报告这次push实际发生了什么
+    // DropOldest 和 OverwriteTop 只作用于同一侧，这一侧在数组中没有元素时新元素会被拒绝
+    // DropOldest 需要把这一侧的元素整体移动一位，所以满了之后每次 push 都是 O(n)，需要环形覆盖时应该使用 SequentialStack
+    pub fn push_with_outcome(&mut self, element: T, stack_num: StackNum) -> PushOutcome<T> {
+        let (mut left, mut right) = self.split_mut();
+        match stack_num {
+            StackNum::First => left.push_with_outcome(element),
+            StackNum::Second => right.push_with_outcome(element),
         }
     }
 
-    // TODO 逻辑扁平化 
     pub fn pop(&mut self, stack_num: StackNum) -> Result<T, &'static str> {
+        let (mut left, mut right) = self.split_mut();
         match stack_num {
-            StackNum::First => {
-                if self.top0 == 0 && self.data[self.top0].is_none() {
-                    Err("Left stack is empty!")
-                } else {
-                    if self.top0 == 0 {
-                        Ok(self.data[self.top0].take().unwrap())
-                    } else {
-                        self.top0 -= 1;
-                        Ok(self.data[self.top0+1].take().unwrap())
-                    }
-                }
+            StackNum::First => left.pop(),
+            StackNum::Second => right.pop(),
+        }
+    }
+
+    pub fn get(&self, stack_num: StackNum) -> Result<&T, &'static str> {
+        let side = stack_num as usize;
+        if let Ok(element) = self.spill[side].peek() {
+            return Ok(element);
+        }
+        if self.lens[side] == 0 {
+            return Err(stack_num.empty_error());
+        }
+        Ok(self.data[self.top_slot(stack_num)].as_ref().unwrap())
+    }
+
+    pub fn get_mut(&mut self, stack_num: StackNum) -> Result<&mut T, &'static str> {
+        let side = stack_num as usize;
+        if !self.spill[side].is_empty() {
+            return self.spill[side].peek_mut();
+        }
+        if self.lens[side] == 0 {
+            return Err(stack_num.empty_error());
+        }
+        let top = self.top_slot(stack_num);
+        Ok(self.data[top].as_mut().unwrap())
+    }
+
+    // 某一侧的视图，实现了 Stack trait，不需要每次都传入 StackNum
+    pub fn side_mut(&mut self, stack_num: StackNum) -> SideStack<'_, T, N> {
+        SideStack { stack: self, side: stack_num }
+    }
+
+    // 拆成左右两个可以同时使用的句柄，它们共享中间的空闲空间
+    pub fn split_mut(&mut self) -> (LeftStack<'_, T>, RightStack<'_, T>) {
+        let policy = self.policy;
+        let data = NonNull::from(&mut self.data[..]).cast::<Option<T>>();
+        let lens = Cell::from_mut(&mut self.lens[..]).as_slice_of_cells();
+        let [left_spill, right_spill] = &mut self.spill;
+        (HalfStack::new(data, N, lens, policy, left_spill), HalfStack::new(data, N, lens, policy, right_spill))
+    }
+}
+
+// 作为 HalfStack 的类型参数，区分 LeftStack 和 RightStack
+pub trait Side {
+    const NUM: StackNum;
+}
+
+pub enum Left {}
+
+pub enum Right {}
+
+impl Side for Left {
+    const NUM: StackNum = StackNum::First;
+}
+
+impl Side for Right {
+    const NUM: StackNum = StackNum::Second;
+}
+
+// split_mut 得到的某一侧的句柄
+// 每个句柄只读写自己这一侧的元素，所以两个句柄可以同时持有；两侧的长度放在 Cell 中，
+// 这样一侧 push 时能看到另一侧当前占用了多少空间
+pub struct HalfStack<'a, T, S: Side> {
+    data: NonNull<Option<T>>,
+    capacity: usize,
+    lens: &'a [Cell<usize>],
+    policy: OverflowPolicy,
+    spill: &'a mut Spill<T>,
+    marker: PhantomData<(&'a mut [Option<T>], S)>,
+}
+
+pub type LeftStack<'a, T> = HalfStack<'a, T, Left>;
+pub type RightStack<'a, T> = HalfStack<'a, T, Right>;
+
+impl<'a, T, S: Side> HalfStack<'a, T, S> {
+    fn new(data: NonNull<Option<T>>, capacity: usize, lens: &'a [Cell<usize>], policy: OverflowPolicy, spill: &'a mut Spill<T>) -> Self {
+        Self { data, capacity, lens, policy, spill, marker: PhantomData }
+    }
+
+    // 这一侧在数组中的元素个数
+    fn own_len(&self) -> usize {
+        self.lens[S::NUM as usize].get()
+    }
+
+    fn set_own_len(&self, len: usize) {
+        self.lens[S::NUM as usize].set(len);
+    }
+
+    // 这一侧在数组中占用的区间，左栈是 data[0..len]，栈顶在末尾；右栈是 data[N-len..N]，栈顶在开头
+    fn region_start(&self) -> usize {
+        match S::NUM {
+            StackNum::First => 0,
+            StackNum::Second => self.capacity - self.own_len(),
+        }
+    }
+
+    fn region(&self) -> &[Option<T>] {
+        // 另一个句柄不会访问这个区间
+        unsafe { slice::from_raw_parts(self.data.as_ptr().add(self.region_start()), self.own_len()) }
+    }
+
+    fn region_mut(&mut self) -> &mut [Option<T>] {
+        unsafe { slice::from_raw_parts_mut(self.data.as_ptr().add(self.region_start()), self.own_len()) }
+    }
+
+    fn top_slot(&mut self) -> Option<&mut Option<T>> {
+        let region = self.region_mut();
+        match S::NUM {
+            StackNum::First => region.last_mut(),
+            StackNum::Second => region.first_mut(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.own_len() + self.spill.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // 两侧共享的空闲位置
+    pub fn remaining(&self) -> usize {
+        self.capacity - self.lens[0].get() - self.lens[1].get()
+    }
+
+    pub fn push(&mut self, element: T) -> Result<(), &'static str> {
+        match self.push_with_outcome(element) {
+            PushOutcome::Rejected(_) => Err("stack is full!"),
+            _ => Ok(()),
+        }
+    }
+
+    // DropOldest 和 OverwriteTop 只作用于这一侧，这一侧在数组中没有元素时新元素会被拒绝
+    // DropOldest 会移动这一侧的全部元素，满了之后每次 push 都是 O(n)
+    pub fn push_with_outcome(&mut self, element: T) -> PushOutcome<T> {
+        // 堆上还有元素时策略一定是 SpillToHeap（见 set_overflow_policy），新元素继续放在堆上
+        if !self.spill.is_empty() {
+            self.spill.push(element);
+            return PushOutcome::Spilled;
+        }
+        if self.remaining() > 0 {
+            self.set_own_len(self.own_len() + 1);
+            *self.top_slot().unwrap() = Some(element);
+            return PushOutcome::Pushed;
+        }
+        match self.policy {
+            OverflowPolicy::Reject => PushOutcome::Rejected(element),
+            OverflowPolicy::SpillToHeap => {
+                self.spill.push(element);
+                PushOutcome::Spilled
             }
-            StackNum::Second => {
-                if self.top1 == N-1 && self.data[self.top1].is_none() {
-                    Err("Right stack is empty!")
-                } else {
-                    if self.top1 == N-1 {
-                        Ok(self.data[self.top1].take().unwrap())
-                    } else {
-                        self.top1 += 1;
-                        Ok(self.data[self.top1-1].take().unwrap())
+            _ if self.own_len() == 0 => PushOutcome::Rejected(element),
+            // 这一侧的元素整体向栈底方向移动一位，腾出栈顶的位置
+            OverflowPolicy::DropOldest => {
+                let region = self.region_mut();
+                let oldest = match S::NUM {
+                    StackNum::First => {
+                        let oldest = region[0].take();
+                        region.rotate_left(1);
+                        oldest
                     }
-                }
+                    StackNum::Second => {
+                        let oldest = region[region.len() - 1].take();
+                        region.rotate_right(1);
+                        oldest
+                    }
+                };
+                *self.top_slot().unwrap() = Some(element);
+                PushOutcome::DroppedOldest(oldest.unwrap())
             }
+            OverflowPolicy::OverwriteTop => PushOutcome::OverwroteTop(self.top_slot().unwrap().replace(element).unwrap()),
         }
     }
 
-    // TODO 逻辑扁平化
-    pub fn get(&self, stack_num: StackNum) -> Result<&T, &'static str> {
-        match stack_num {
-            StackNum::First => {
-                if self.top0 == 0 && self.data[self.top0].is_none() {
-                    Err("Left stack is empty!")
+    pub fn pop(&mut self) -> Result<T, &'static str> {
+        if let Ok(element) = self.spill.pop() {
+            return Ok(element);
+        }
+        let element = self.top_slot().ok_or(S::NUM.empty_error())?.take().unwrap();
+        self.set_own_len(self.own_len() - 1);
+        Ok(element)
+    }
+
+    pub fn peek(&self) -> Result<&T, &'static str> {
+        self.iter().next().ok_or(S::NUM.empty_error())
+    }
+
+    pub fn peek_mut(&mut self) -> Result<&mut T, &'static str> {
+        if !self.spill.is_empty() {
+            return self.spill.peek_mut();
+        }
+        Ok(self.top_slot().ok_or(S::NUM.empty_error())?.as_mut().unwrap())
+    }
+
+    // 从栈顶到栈底遍历
+    pub fn iter(&self) -> HalfIter<'_, T> {
+        HalfIter { spill: self.spill.iter(), data: self.region().iter(), from_back: S::NUM == StackNum::First }
+    }
+}
+
+impl<T, S: Side> Stack<T> for HalfStack<'_, T, S> {
+    fn push(&mut self, element: T) -> Result<(), &'static str> {
+        HalfStack::push(self, element)
+    }
+
+    fn pop(&mut self) -> Result<T, &'static str> {
+        HalfStack::pop(self)
+    }
+
+    fn peek(&self) -> Result<&T, &'static str> {
+        HalfStack::peek(self)
+    }
+
+    fn peek_mut(&mut self) -> Result<&mut T, &'static str> {
+        HalfStack::peek_mut(self)
+    }
+
+    fn len(&self) -> usize {
+        HalfStack::len(self)
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        match self.policy {
+            OverflowPolicy::SpillToHeap => None,
+            _ => Some(self.len() + self.remaining()),
+        }
+    }
+}
+
+impl<'a, T, S: Side> IntoIterator for &'a HalfStack<'_, T, S> {
+    type Item = &'a T;
+    type IntoIter = HalfIter<'a, T>;
+
+    fn into_iter(self) -> HalfIter<'a, T> {
+        self.iter()
+    }
+}
+
+pub struct HalfIter<'a, T> {
+    spill: linked_stack::Iter<'a, T>,
+    data: slice::Iter<'a, Option<T>>,
+    // 左栈的栈顶在区间末尾，需要从后往前遍历
+    from_back: bool,
+}
+
+impl<'a, T> Iterator for HalfIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if let Some(element) = self.spill.next() {
+            return Some(element);
+        }
+        let slot = if self.from_back { self.data.next_back() } else { self.data.next() };
+        slot.map(|element| element.as_ref().unwrap())
+    }
+}
+
+pub struct SideStack<'a, T, const N: usize> {
+    stack: &'a mut SqDoubleStack<T, N>,
+    side: StackNum,
+}
+
+impl<T, const N: usize> Stack<T> for SideStack<'_, T, N> {
+    fn push(&mut self, element: T) -> Result<(), &'static str> {
+        self.stack.push(element, self.side)
+    }
+
+    fn pop(&mut self) -> Result<T, &'static str> {
+        self.stack.pop(self.side)
+    }
+
+    fn peek(&self) -> Result<&T, &'static str> {
+        self.stack.get(self.side)
+    }
+
+    fn peek_mut(&mut self) -> Result<&mut T, &'static str> {
+        self.stack.get_mut(self.side)
+    }
+
+    fn len(&self) -> usize {
+        self.stack.len(self.side)
+    }
+
+    // 两侧共享中间的空闲空间，所以容量是自己的元素个数加上当前的空闲位置，允许溢出到堆上时没有上限
+    fn capacity(&self) -> Option<usize> {
+        match self.stack.policy {
+            OverflowPolicy::SpillToHeap => None,
+            _ => Some(self.len() + self.stack.remaining()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIDES: [StackNum; 2] = [StackNum::First, StackNum::Second];
+
+    #[test]
+    fn test_all_slots_usable() {
+        let mut stack: SqDoubleStack<i32, 4> = SqDoubleStack::new();
+        assert_eq!(stack.remaining(), 4);
+        stack.push(1, StackNum::First).unwrap();
+        stack.push(2, StackNum::First).unwrap();
+        stack.push(3, StackNum::First).unwrap();
+        stack.push(4, StackNum::Second).unwrap();
+        assert_eq!(stack.remaining(), 0);
+        assert_eq!(stack.len(StackNum::First), 3);
+        assert_eq!(stack.len(StackNum::Second), 1);
+        assert_eq!(stack.push(5, StackNum::First), Err("stack is full!"));
+        assert_eq!(stack.push_with_outcome(5, StackNum::Second), PushOutcome::Rejected(5));
+
+        // 只剩一个元素的一侧不会被误认为是空的
+        assert_eq!(stack.pop(StackNum::First).unwrap(), 3);
+        assert_eq!(stack.pop(StackNum::First).unwrap(), 2);
+        assert!(!stack.is_empty(StackNum::First));
+        assert_eq!(*stack.get(StackNum::First).unwrap(), 1);
+        assert_eq!(stack.pop(StackNum::First).unwrap(), 1);
+        assert!(stack.is_empty(StackNum::First));
+        assert_eq!(stack.pop(StackNum::First), Err("Left stack is empty!"));
+        assert_eq!(stack.pop(StackNum::Second).unwrap(), 4);
+        assert_eq!(stack.get(StackNum::Second), Err("Right stack is empty!"));
+    }
+
+    #[test]
+    fn test_zero_and_one_capacity() {
+        let mut empty: SqDoubleStack<i32, 0> = SqDoubleStack::new();
+        assert_eq!(empty.remaining(), 0);
+        for side in SIDES {
+            assert!(empty.is_empty(side));
+            assert!(empty.push(1, side).is_err());
+            assert!(empty.pop(side).is_err());
+        }
+
+        let mut single: SqDoubleStack<i32, 1> = SqDoubleStack::new();
+        single.push(7, StackNum::Second).unwrap();
+        assert!(single.push(8, StackNum::First).is_err());
+        assert_eq!(single.pop(StackNum::Second).unwrap(), 7);
+        single.push(8, StackNum::First).unwrap();
+        assert_eq!(*single.get(StackNum::First).unwrap(), 8);
+    }
+
+    // 枚举长度为 steps 的所有操作序列，每一步之后都和两个 Vec 组成的模型比较
+    fn exhaustive<const N: usize>(steps: u32) {
+        // 每一步有四种操作：两侧各自的 push 和 pop
+        for mut code in 0..4usize.pow(steps) {
+            let mut stack: SqDoubleStack<usize, N> = SqDoubleStack::new();
+            let mut model: [Vec<usize>; 2] = [Vec::new(), Vec::new()];
+            for step in 0..steps as usize {
+                let side = SIDES[code % 2];
+                let is_push = code % 4 < 2;
+                code /= 4;
+                if is_push {
+                    let fits = model[0].len() + model[1].len() < N;
+                    assert_eq!(stack.push(step, side).is_ok(), fits);
+                    if fits {
+                        model[side as usize].push(step);
+                    }
                 } else {
-                    Ok(self.data[self.top0].as_ref().unwrap())
+                    assert_eq!(stack.pop(side).ok(), model[side as usize].pop());
                 }
-            }
-            StackNum::Second => {
-                if self.top1 == N-1 && self.data[self.top1].is_none() {
-                    Err("Right stack is empty!")
-                } else {
-                    Ok(self.data[self.top1].as_ref().unwrap())
+                for side in SIDES {
+                    let model_side = &model[side as usize];
+                    assert_eq!(stack.len(side), model_side.len());
+                    assert_eq!(stack.is_empty(side), model_side.is_empty());
+                    assert_eq!(stack.get(side).ok(), model_side.last());
                 }
+                assert_eq!(stack.remaining(), N - model[0].len() - model[1].len());
             }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_exhaustive_state_space() {
+        // 步数比容量多两步，可以到达所有状态并且在满的状态下继续操作
+        exhaustive::<0>(3);
+        exhaustive::<1>(4);
+        exhaustive::<2>(5);
+        exhaustive::<3>(6);
+        exhaustive::<4>(7);
+        exhaustive::<5>(8);
+    }
+
+    #[test]
+    fn test_drop_oldest_per_side() {
+        let mut stack: SqDoubleStack<i32, 4> = SqDoubleStack::with_policy(OverflowPolicy::DropOldest);
+        stack.push(1, StackNum::First).unwrap();
+        stack.push(2, StackNum::First).unwrap();
+        stack.push(10, StackNum::Second).unwrap();
+        stack.push(20, StackNum::Second).unwrap();
+        assert_eq!(stack.push_with_outcome(3, StackNum::First), PushOutcome::DroppedOldest(1));
+        assert_eq!(stack.push_with_outcome(30, StackNum::Second), PushOutcome::DroppedOldest(10));
+        assert_eq!(stack.pop(StackNum::First).unwrap(), 3);
+        assert_eq!(stack.pop(StackNum::First).unwrap(), 2);
+        assert!(stack.pop(StackNum::First).is_err());
+        assert_eq!(stack.pop(StackNum::Second).unwrap(), 30);
+        assert_eq!(stack.pop(StackNum::Second).unwrap(), 20);
+        assert!(stack.pop(StackNum::Second).is_err());
+    }
+
+    #[test]
+    fn test_overwrite_top_and_empty_side() {
+        let mut stack: SqDoubleStack<i32, 2> = SqDoubleStack::with_policy(OverflowPolicy::OverwriteTop);
+        stack.push(1, StackNum::First).unwrap();
+        stack.push(2, StackNum::First).unwrap();
+        assert_eq!(stack.push_with_outcome(3, StackNum::First), PushOutcome::OverwroteTop(2));
+        // 右栈在数组中没有元素，既不能丢弃也不能覆盖
+        assert_eq!(stack.push_with_outcome(9, StackNum::Second), PushOutcome::Rejected(9));
+        assert_eq!(*stack.get(StackNum::First).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_spill_to_heap_per_side() {
+        let mut stack: SqDoubleStack<i32, 2> = SqDoubleStack::with_policy(OverflowPolicy::SpillToHeap);
+        stack.push(1, StackNum::First).unwrap();
+        stack.push(2, StackNum::Second).unwrap();
+        assert_eq!(stack.push_with_outcome(3, StackNum::First), PushOutcome::Spilled);
+        assert_eq!(stack.push_with_outcome(4, StackNum::Second), PushOutcome::Spilled);
+        assert_eq!(stack.len(StackNum::First), 2);
+        assert_eq!(stack.side_mut(StackNum::First).capacity(), None);
+        assert_eq!(*stack.get(StackNum::Second).unwrap(), 4);
+        assert_eq!(stack.pop(StackNum::Second).unwrap(), 4);
+
+        // 右栈已经没有溢出的元素，但左栈还有，仍然不能换成其他策略
+        assert_eq!(stack.set_overflow_policy(OverflowPolicy::Reject), Err("Spilled elements must be popped first!"));
+        assert_eq!(stack.pop(StackNum::First).unwrap(), 3);
+        stack.set_overflow_policy(OverflowPolicy::Reject).unwrap();
+        assert_eq!(stack.push(5, StackNum::First), Err("stack is full!"));
+        assert_eq!(stack.pop(StackNum::First).unwrap(), 1);
+        assert_eq!(stack.pop(StackNum::Second).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_split_mut_share_free_space() {
+        let mut stack: SqDoubleStack<i32, 5> = SqDoubleStack::new();
+        stack.push(0, StackNum::Second).unwrap();
+        {
+            let (mut left, mut right) = stack.split_mut();
+            // 两个句柄同时持有，交替使用
+            for i in 1..=2 {
+                left.push(i).unwrap();
+                right.push(i * 10).unwrap();
+            }
+            assert_eq!(left.remaining(), 0);
+            assert_eq!(right.remaining(), 0);
+            assert_eq!(left.push(3), Err("stack is full!"));
+            assert_eq!(left.iter().copied().collect::<Vec<_>>(), vec![2, 1]);
+            assert_eq!(right.iter().copied().collect::<Vec<_>>(), vec![20, 10, 0]);
+
+            // 一侧 pop 腾出的空间另一侧可以使用
+            assert_eq!(left.pop().unwrap(), 2);
+            right.push(30).unwrap();
+            *left.peek_mut().unwrap() += 100;
+            assert_eq!(*left.peek().unwrap(), 101);
+            assert_eq!(*right.peek().unwrap(), 30);
+            assert_eq!(right.len(), 4);
+            assert_eq!(Stack::capacity(&left), Some(1));
+        }
+        assert_eq!(stack.len(StackNum::First), 1);
+        assert_eq!(stack.pop(StackNum::Second).unwrap(), 30);
+        assert_eq!(stack.pop(StackNum::First).unwrap(), 101);
+        assert_eq!(stack.pop(StackNum::First), Err("Left stack is empty!"));
+    }
+
+    #[test]
+    fn test_split_mut_policies() {
+        let mut stack: SqDoubleStack<i32, 3> = SqDoubleStack::with_policy(OverflowPolicy::DropOldest);
+        let (mut left, mut right) = stack.split_mut();
+        left.push(1).unwrap();
+        left.push(2).unwrap();
+        right.push(9).unwrap();
+        assert_eq!(left.push_with_outcome(3), PushOutcome::DroppedOldest(1));
+        assert_eq!(right.push_with_outcome(8), PushOutcome::DroppedOldest(9));
+        assert_eq!(left.iter().copied().collect::<Vec<_>>(), vec![3, 2]);
+        assert_eq!(right.pop().unwrap(), 8);
+
+        let mut stack: SqDoubleStack<i32, 1> = SqDoubleStack::with_policy(OverflowPolicy::SpillToHeap);
+        let (mut left, mut right) = stack.split_mut();
+        right.push(1).unwrap();
+        right.push(2).unwrap();
+        left.push(3).unwrap();
+        assert_eq!((&right).into_iter().copied().collect::<Vec<_>>(), vec![2, 1]);
+        assert_eq!(left.pop().unwrap(), 3);
+        assert!(left.pop().is_err());
+        assert_eq!(right.pop().unwrap(), 2);
+    }
+
+    // 第一阶段把数据放进左栈，第二阶段从左栈取出处理后放进右栈，两个阶段共用同一块空间
+    #[test]
+    fn test_split_mut_producer_consumer() {
+        let mut stack: SqDoubleStack<u32, 8> = SqDoubleStack::new();
+        let (mut input, mut output) = stack.split_mut();
+        for value in 0..8 {
+            input.push(value).unwrap();
+        }
+        assert_eq!(output.remaining(), 0);
+        while let Ok(value) = input.pop() {
+            output.push(value * 2).unwrap();
+        }
+        assert!(input.is_empty());
+        assert_eq!(output.iter().copied().collect::<Vec<_>>(), vec![0, 2, 4, 6, 8, 10, 12, 14]);
+    }
+}
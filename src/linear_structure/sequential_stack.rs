@@ -4,76 +4,327 @@
  * @LastEditors: MerlinSMQWQ MerlinSMQWQ@proton.me
  * @LastEditTime: 2025-12-02 17:35:56
  * @FilePath: \Rust-Data-Struct\src\linear_list\sequential_stack.rs
- * @Description: 线性栈，但是实现的是底部入栈，也就是从data[0]处开始插入数据，实际上使用顶部入栈的实现方法会更好
+ * @Description: 线性栈，栈底在data[bottom]处，栈顶向N的方向增长，push/pop/get都是O(1)
  */
 
+// data 被当作环形缓冲区使用，平时 bottom 始终是 0，只有 DropOldest 策略丢弃栈底元素时 bottom 才会向后移动
+// 使用 SpillToHeap 策略时，放不下的元素存放在 spill 链栈中，它们总是位于数组中所有元素的上方
+
+use std::fmt;
+
+use super::{
+    linked_stack,
+    overflow::{OverflowPolicy, PushOutcome, Spill},
+    stack::Stack,
+};
+
 pub struct SequentialStack<T, const N: usize> {
     data: [Option<T>; N],
+    bottom: usize,
+    // 数组中的元素个数，不包括溢出到堆上的元素
     len: usize,
+    policy: OverflowPolicy,
+    spill: Spill<T>,
+}
+
+impl<T, const N: usize> Default for SequentialStack<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T, const N: usize> SequentialStack<T, N> {
     pub fn new() -> Self {
+        Self::with_policy(OverflowPolicy::Reject)
+    }
+
+    pub fn with_policy(policy: OverflowPolicy) -> Self {
         Self {
             data: [const { None }; N],
+            bottom: 0,
             len: 0,
+            policy,
+            spill: Spill::new(),
+        }
+    }
+
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    // 还有元素溢出在堆上时只能保持 SpillToHeap，否则新元素要么压到它们下面，要么绕过新的策略
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy) -> Result<(), &'static str> {
+        if !self.spill.is_empty() && policy != OverflowPolicy::SpillToHeap {
+            return Err("Spilled elements must be popped first!");
         }
+        self.policy = policy;
+        Ok(())
     }
 
+    // 数组是否已经放满，和 capacity 一样只看数组，不考虑溢出到堆上的元素
+    // SpillToHeap 策略下满了之后 push 仍然成功，这时 len 会超过 capacity
     pub fn is_full(&self) -> bool {
         self.len == N
     }
 
     pub fn len(&self) -> usize {
-        self.len
+        self.len + self.spill.len()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.len == 0
+        self.len() == 0
     }
 
     pub fn capacity(&self) -> usize {
         N
     }
 
+    // 从栈底数第i个元素在data中的下标
+    fn slot(&self, i: usize) -> usize {
+        (self.bottom + i) % N
+    }
+
+    // 满了之后按照当前的溢出策略处理，默认的 Reject 策略返回 "Stack overflow!"
     pub fn push(&mut self, element: T) -> Result<(), &'static str> {
-        if self.is_full() {
-            Err("Stack overflow!")
-        } else {
-            if self.len == 0 {
-                self.data[0] = Some(element);
-            } else {
-                for i in (0..self.len).rev() {
-                    self.data[i + 1] = self.data[i].take();
-                }
-                self.data[0] = Some(element);
-            }
+        match self.push_with_outcome(element) {
+            PushOutcome::Rejected(_) => Err("Stack overflow!"),
+            _ => Ok(()),
+        }
+    }
+
+    // 和push相同，但是会报告这次push实际发生了什么
+    pub fn push_with_outcome(&mut self, element: T) -> PushOutcome<T> {
+        // 已经有元素溢出到堆上时，新元素只能继续放在堆上，否则会压到它们下面
+        // set_overflow_policy 保证这时的策略一定是 SpillToHeap
+        if !self.spill.is_empty() {
+            self.spill.push(element);
+            return PushOutcome::Spilled;
+        }
+        if !self.is_full() {
+            // 栈顶在data[len-1]，新元素直接放在data[len]，不需要移动其他元素
+            let slot = self.slot(self.len);
+            self.data[slot] = Some(element);
             self.len += 1;
-            Ok(())
+            return PushOutcome::Pushed;
+        }
+        match self.policy {
+            OverflowPolicy::Reject => PushOutcome::Rejected(element),
+            OverflowPolicy::SpillToHeap => {
+                self.spill.push(element);
+                PushOutcome::Spilled
+            }
+            _ if N == 0 => PushOutcome::Rejected(element),
+            OverflowPolicy::DropOldest => {
+                // 数组已满，栈顶的下一个位置就是栈底，覆盖它之后栈底向后移动一位
+                let oldest = self.data[self.bottom].replace(element).unwrap();
+                self.bottom = (self.bottom + 1) % N;
+                PushOutcome::DroppedOldest(oldest)
+            }
+            OverflowPolicy::OverwriteTop => {
+                let top = self.slot(self.len - 1);
+                PushOutcome::OverwroteTop(self.data[top].replace(element).unwrap())
+            }
         }
     }
 
     pub fn pop(&mut self) -> Result<T, &'static str> {
-        if self.is_empty() {
+        if let Ok(element) = self.spill.pop() {
+            Ok(element)
+        } else if self.len == 0 {
             Err("Stack is empty!")
         } else {
-            let result = self.data[0].take().unwrap();
-            
-            for i in 0..(self.len-1) {
-                self.data[i] = self.data[i+1].take();
+            self.len -= 1;
+            let slot = self.slot(self.len);
+            let element = self.data[slot].take().unwrap();
+            if self.len == 0 {
+                self.bottom = 0;
             }
+            Ok(element)
+        }
+    }
 
-            self.len -= 1;
-            Ok(result)
+    // 取出栈底元素，bottom 向后移动一位即可，O(1)
+    pub fn pop_bottom(&mut self) -> Result<T, &'static str> {
+        if self.len == 0 {
+            // 数组为空时剩下的元素都在堆上
+            return self.spill.pop_bottom();
         }
+        let element = self.data[self.bottom].take().unwrap();
+        self.len -= 1;
+        self.bottom = if self.len == 0 { 0 } else { (self.bottom + 1) % N };
+        Ok(element)
     }
 
     pub fn get(&self) -> Result<&T, &'static str> {
-        if self.is_empty() {
+        self.peek_at(0).map_err(|_| "Stack is empty!")
+    }
+
+    pub fn get_mut(&mut self) -> Result<&mut T, &'static str> {
+        if !self.spill.is_empty() {
+            self.spill.peek_mut()
+        } else if self.len == 0 {
             Err("Stack is empty!")
         } else {
-            Ok(self.data[0].as_ref().unwrap())
+            let slot = self.slot(self.len - 1);
+            Ok(self.data[slot].as_mut().unwrap())
+        }
+    }
+
+    // 和get_mut相同，只是命名上和peek_at对应
+    pub fn peek_mut(&mut self) -> Result<&mut T, &'static str> {
+        self.get_mut()
+    }
+
+    // depth从栈顶开始计数，peek_at(0)就是栈顶元素
+    // 数组中的元素直接按下标访问，O(1)；溢出到堆上的元素在数组上方，只能沿链栈走过去
+    pub fn peek_at(&self, depth: usize) -> Result<&T, &'static str> {
+        let spilled = self.spill.len();
+        if depth < spilled {
+            return self.spill.iter().nth(depth).ok_or("Out of stack!");
+        }
+        let depth = depth - spilled;
+        if depth >= self.len {
+            return Err("Out of stack!");
+        }
+        Ok(self.data[self.slot(self.len - 1 - depth)].as_ref().unwrap())
+    }
+
+    // 只保留栈底的depth个元素，depth不小于当前长度时什么也不做
+    pub fn truncate(&mut self, depth: usize) {
+        while self.len() > depth {
+            let _ = self.pop();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    // 从栈顶到栈底遍历
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { spill: self.spill.iter(), spilled: self.spill.len(), data: &self.data, bottom: self.bottom, remaining: self.len }
+    }
+}
+
+impl<T, const N: usize> Stack<T> for SequentialStack<T, N> {
+    fn push(&mut self, element: T) -> Result<(), &'static str> {
+        SequentialStack::push(self, element)
+    }
+
+    fn pop(&mut self) -> Result<T, &'static str> {
+        SequentialStack::pop(self)
+    }
+
+    fn peek(&self) -> Result<&T, &'static str> {
+        self.get()
+    }
+
+    fn peek_mut(&mut self) -> Result<&mut T, &'static str> {
+        self.get_mut()
+    }
+
+    fn len(&self) -> usize {
+        SequentialStack::len(self)
+    }
+
+    // 允许溢出到堆上时没有容量上限
+    fn capacity(&self) -> Option<usize> {
+        match self.policy {
+            OverflowPolicy::SpillToHeap => None,
+            _ => Some(N),
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    spill: linked_stack::Iter<'a, T>,
+    spilled: usize,
+    data: &'a [Option<T>],
+    bottom: usize,
+    // 数组中还没有遍历到的元素个数
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if let Some(element) = self.spill.next() {
+            self.spilled -= 1;
+            return Some(element);
+        }
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        self.data[(self.bottom + self.remaining) % self.data.len()].as_ref()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.spilled + self.remaining;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+// 按值遍历，每次next相当于一次pop，所以是后进先出的顺序
+pub struct IntoIter<T, const N: usize> {
+    stack: SequentialStack<T, N>,
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.stack.pop().ok()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.stack.len(), Some(self.stack.len()))
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> IntoIterator for SequentialStack<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> IntoIter<T, N> {
+        IntoIter { stack: self }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a SequentialStack<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+// 只显示有效元素，栈顶在前
+impl<T: fmt::Debug, const N: usize> fmt::Debug for SequentialStack<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// 类似调用栈的格式，每行一个元素，#0是栈顶
+impl<T: fmt::Display, const N: usize> fmt::Display for SequentialStack<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "<empty stack>");
+        }
+        for (depth, element) in self.iter().enumerate() {
+            if depth > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "#{} {}", depth, element)?;
         }
+        Ok(())
     }
 }
 
@@ -265,4 +516,256 @@ mod tests {
         assert_eq!(stack.pop().unwrap(), 1);
         assert!(stack.is_empty());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_iter_and_peek_at() {
+        let mut stack: SequentialStack<i32, 4> = SequentialStack::new();
+        assert_eq!(stack.iter().next(), None);
+        assert_eq!(stack.peek_at(0).unwrap_err(), "Out of stack!");
+
+        stack.push(1).unwrap();
+        stack.push(2).unwrap();
+        stack.push(3).unwrap();
+        assert_eq!(stack.iter().copied().collect::<Vec<_>>(), vec![3, 2, 1]);
+        assert_eq!(stack.iter().len(), 3);
+        assert_eq!(*stack.peek_at(0).unwrap(), 3);
+        assert_eq!(*stack.peek_at(2).unwrap(), 1);
+        assert!(stack.peek_at(3).is_err());
+        assert_eq!((&stack).into_iter().count(), 3);
+    }
+
+    #[test]
+    fn test_get_mut_and_peek_mut() {
+        let mut stack: SequentialStack<String, 2> = SequentialStack::new();
+        assert!(stack.get_mut().is_err());
+        assert!(stack.peek_mut().is_err());
+
+        stack.push("a".to_string()).unwrap();
+        stack.push("b".to_string()).unwrap();
+        stack.get_mut().unwrap().push('!');
+        stack.peek_mut().unwrap().push('?');
+        assert_eq!(stack.pop().unwrap(), "b!?");
+        assert_eq!(stack.pop().unwrap(), "a");
+    }
+
+    #[test]
+    fn test_truncate_and_clear() {
+        let mut stack: SequentialStack<i32, 5> = SequentialStack::new();
+        for i in 1..=5 {
+            stack.push(i).unwrap();
+        }
+        stack.truncate(7);
+        assert_eq!(stack.len(), 5);
+        stack.truncate(2);
+        assert_eq!(stack.len(), 2);
+        assert_eq!(*stack.get().unwrap(), 2);
+        stack.push(9).unwrap();
+        assert_eq!(stack.iter().copied().collect::<Vec<_>>(), vec![9, 2, 1]);
+        stack.clear();
+        assert!(stack.is_empty());
+        assert!(stack.get().is_err());
+    }
+
+    #[test]
+    fn test_debug_and_display() {
+        let mut stack: SequentialStack<&str, 4> = SequentialStack::new();
+        assert_eq!(format!("{:?}", stack), "[]");
+        assert_eq!(stack.to_string(), "<empty stack>");
+
+        stack.push("main").unwrap();
+        stack.push("parse").unwrap();
+        stack.push("next_token").unwrap();
+        assert_eq!(format!("{:?}", stack), r#"["next_token", "parse", "main"]"#);
+        assert_eq!(stack.to_string(), "#0 next_token\n#1 parse\n#2 main");
+    }
+
+    #[test]
+    fn test_into_iter_lifo() {
+        let mut stack: SequentialStack<String, 3> = SequentialStack::new();
+        stack.push("first".to_string()).unwrap();
+        stack.push("second".to_string()).unwrap();
+        stack.push("third".to_string()).unwrap();
+
+        let mut iter = stack.into_iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next().unwrap(), "third");
+        assert_eq!(iter.collect::<Vec<_>>(), vec!["second", "first"]);
+    }
+}
+
+#[cfg(test)]
+mod overflow_policy_tests {
+    use super::*;
+
+    #[test]
+    fn test_reject_is_default() {
+        let mut stack: SequentialStack<i32, 2> = SequentialStack::new();
+        assert_eq!(stack.overflow_policy(), OverflowPolicy::Reject);
+        assert_eq!(stack.push_with_outcome(1), PushOutcome::Pushed);
+        assert_eq!(stack.push_with_outcome(2), PushOutcome::Pushed);
+        assert_eq!(stack.push_with_outcome(3), PushOutcome::Rejected(3));
+        assert_eq!(stack.iter().copied().collect::<Vec<_>>(), vec![2, 1]);
+    }
+
+    #[test]
+    fn test_drop_oldest_ring() {
+        let mut history: SequentialStack<i32, 3> = SequentialStack::with_policy(OverflowPolicy::DropOldest);
+        for i in 1..=3 {
+            assert_eq!(history.push_with_outcome(i), PushOutcome::Pushed);
+        }
+        assert_eq!(history.push_with_outcome(4), PushOutcome::DroppedOldest(1));
+        assert_eq!(history.push_with_outcome(5), PushOutcome::DroppedOldest(2));
+        assert!(history.push(6).is_ok());
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.iter().copied().collect::<Vec<_>>(), vec![6, 5, 4]);
+        assert_eq!(*history.peek_at(2).unwrap(), 4);
+
+        // 环绕之后的出栈和再次入栈
+        assert_eq!(history.pop().unwrap(), 6);
+        history.push(7).unwrap();
+        assert_eq!(history.push_with_outcome(8), PushOutcome::DroppedOldest(4));
+        assert_eq!(history.into_iter().collect::<Vec<_>>(), vec![8, 7, 5]);
+    }
+
+    #[test]
+    fn test_overwrite_top() {
+        let mut stack: SequentialStack<&str, 2> = SequentialStack::with_policy(OverflowPolicy::OverwriteTop);
+        stack.push("a").unwrap();
+        stack.push("b").unwrap();
+        assert_eq!(stack.push_with_outcome("c"), PushOutcome::OverwroteTop("b"));
+        assert_eq!(stack.len(), 2);
+        assert_eq!(stack.pop().unwrap(), "c");
+        assert_eq!(stack.pop().unwrap(), "a");
+    }
+
+    #[test]
+    fn test_spill_to_heap() {
+        let mut stack: SequentialStack<i32, 2> = SequentialStack::with_policy(OverflowPolicy::SpillToHeap);
+        assert_eq!(Stack::capacity(&stack), None);
+        stack.push(1).unwrap();
+        stack.push(2).unwrap();
+        assert_eq!(stack.push_with_outcome(3), PushOutcome::Spilled);
+        assert_eq!(stack.push_with_outcome(4), PushOutcome::Spilled);
+        assert_eq!(stack.len(), 4);
+        assert!(stack.is_full());
+        assert_eq!(*stack.get().unwrap(), 4);
+        *stack.get_mut().unwrap() += 10;
+        assert_eq!(stack.iter().copied().collect::<Vec<_>>(), vec![14, 3, 2, 1]);
+        assert_eq!(stack.iter().len(), 4);
+        assert_eq!(*stack.peek_at(2).unwrap(), 2);
+
+        // 溢出的元素还在堆上时，即使数组有空位也继续放到堆上，保证后进先出
+        assert_eq!(stack.pop().unwrap(), 14);
+        assert_eq!(stack.push_with_outcome(5), PushOutcome::Spilled);
+        assert_eq!(stack.iter().copied().collect::<Vec<_>>(), vec![5, 3, 2, 1]);
+
+        // 堆上的元素弹出之前不能换成其他策略
+        assert_eq!(stack.set_overflow_policy(OverflowPolicy::Reject), Err("Spilled elements must be popped first!"));
+        assert_eq!(stack.overflow_policy(), OverflowPolicy::SpillToHeap);
+        assert_eq!(stack.pop().unwrap(), 5);
+        assert_eq!(stack.pop().unwrap(), 3);
+        stack.set_overflow_policy(OverflowPolicy::Reject).unwrap();
+        assert_eq!(Stack::capacity(&stack), Some(2));
+        assert_eq!(stack.push_with_outcome(6), PushOutcome::Rejected(6));
+        assert_eq!(stack.into_iter().collect::<Vec<_>>(), vec![2, 1]);
+    }
+
+    #[test]
+    fn test_peek_at_ring_and_spill() {
+        let mut ring: SequentialStack<i32, 3> = SequentialStack::with_policy(OverflowPolicy::DropOldest);
+        for i in 1..=5 {
+            ring.push(i).unwrap();
+        }
+        assert_eq!([0, 1, 2].map(|depth| *ring.peek_at(depth).unwrap()), [5, 4, 3]);
+        assert_eq!(ring.peek_at(3), Err("Out of stack!"));
+
+        let mut stack: SequentialStack<i32, 2> = SequentialStack::with_policy(OverflowPolicy::SpillToHeap);
+        for i in 1..=4 {
+            stack.push(i).unwrap();
+        }
+        assert_eq!([0, 1, 2, 3].map(|depth| *stack.peek_at(depth).unwrap()), [4, 3, 2, 1]);
+        assert_eq!(stack.peek_at(4), Err("Out of stack!"));
+    }
+
+    // 没有溢出时不占用链栈，溢出的元素全部弹出后链栈也随之释放
+    #[test]
+    fn test_spill_allocated_on_demand() {
+        assert_eq!(std::mem::size_of::<Spill<i32>>(), std::mem::size_of::<usize>());
+        let mut stack: SequentialStack<i32, 1> = SequentialStack::with_policy(OverflowPolicy::SpillToHeap);
+        stack.push(1).unwrap();
+        assert!(stack.spill.is_empty());
+        stack.push(2).unwrap();
+        assert!(!stack.spill.is_empty());
+        assert_eq!(stack.pop().unwrap(), 2);
+        assert!(stack.spill.is_empty());
+    }
+
+    #[test]
+    fn test_pop_bottom() {
+        let mut stack: SequentialStack<i32, 3> = SequentialStack::with_policy(OverflowPolicy::SpillToHeap);
+        for i in 1..=5 {
+            stack.push(i).unwrap();
+        }
+        assert_eq!(stack.pop_bottom().unwrap(), 1);
+        assert_eq!(stack.pop_bottom().unwrap(), 2);
+        assert_eq!(stack.iter().copied().collect::<Vec<_>>(), vec![5, 4, 3]);
+        assert_eq!(stack.pop_bottom().unwrap(), 3);
+        assert_eq!(stack.pop_bottom().unwrap(), 4);
+        assert_eq!(stack.pop().unwrap(), 5);
+        assert_eq!(stack.pop_bottom(), Err("Stack is empty!"));
+    }
+
+    #[test]
+    fn test_zero_capacity_policies() {
+        for policy in [OverflowPolicy::Reject, OverflowPolicy::DropOldest, OverflowPolicy::OverwriteTop] {
+            let mut stack: SequentialStack<i32, 0> = SequentialStack::with_policy(policy);
+            assert_eq!(stack.push_with_outcome(1), PushOutcome::Rejected(1));
+            assert!(stack.is_empty());
+        }
+        let mut stack: SequentialStack<i32, 0> = SequentialStack::with_policy(OverflowPolicy::SpillToHeap);
+        assert_eq!(stack.push_with_outcome(1), PushOutcome::Spilled);
+        assert_eq!(stack.pop().unwrap(), 1);
+    }
+}
+
+#[cfg(test)]
+mod performance_tests {
+    use super::*;
+    use std::cell::Cell;
+
+    // 记录 drop 次数，push 和 pop 如果挪动或者重建了已有的元素就会多出 drop
+    struct Tracked<'a>(usize, &'a Cell<usize>);
+
+    impl Drop for Tracked<'_> {
+        fn drop(&mut self) {
+            self.1.set(self.1.get() + 1);
+        }
+    }
+
+    fn addresses<T, const N: usize>(stack: &SequentialStack<T, N>) -> Vec<*const T> {
+        stack.iter().map(|element| element as *const T).collect()
+    }
+
+    // push 和 pop 都是 O(1)：只写入或取出栈顶的一个槽位，已有元素的地址保持不变，也不会被 drop
+    #[test]
+    fn test_push_pop_never_move_existing_elements() {
+        let drops = Cell::new(0);
+        let mut stack: Box<SequentialStack<Tracked<'_>, 64>> = Box::default();
+        for i in 0..64 {
+            let before = addresses(&stack);
+            assert!(stack.push(Tracked(i, &drops)).is_ok());
+            // iter 从栈顶开始，去掉新的栈顶之后和 push 之前完全相同
+            assert_eq!(addresses(&stack)[1..], before[..]);
+        }
+        assert_eq!(drops.get(), 0);
+        for i in (0..64).rev() {
+            let before = addresses(&stack);
+            let top = stack.pop().unwrap();
+            assert_eq!(top.0, i);
+            assert_eq!(addresses(&stack)[..], before[1..]);
+            assert_eq!(drops.get(), 63 - i);
+            drop(top);
+        }
+        assert_eq!(drops.get(), 64);
+    }
+}
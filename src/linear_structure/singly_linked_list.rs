@@ -10,6 +10,8 @@
 // NonNull是一个包装过的原始指针，可以保证指针部位null，可以喝Box配合来管理堆内存
 use std::ptr::NonNull;
 
+use super::pool::{NodePool, PoolStats};
+
 pub struct Node<T: std::fmt::Debug> {
     pub data: T,
     pub next: Option<NonNull<Node<T>>>,
@@ -27,6 +29,7 @@ impl<T: std::fmt::Debug> Node<T> {
 pub struct SinglyLinkedList<T: std::fmt::Debug> {
     pub length: usize,
     pub next: Option<NonNull<Node<T>>>,
+    pool: NodePool<Node<T>>,
 }
 
 impl<T: std::fmt::Debug> Drop for SinglyLinkedList<T> {
@@ -39,22 +42,46 @@ impl<T: std::fmt::Debug> Drop for SinglyLinkedList<T> {
     }
 }
 
+impl<T: std::fmt::Debug> Default for SinglyLinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T: std::fmt::Debug> SinglyLinkedList<T> {
     pub fn new() -> Self {
+        Self::with_pool_capacity(0)
+    }
+
+    // 删除的结点最多保留 capacity 个，插入时直接复用
+    pub fn with_pool_capacity(capacity: usize) -> Self {
         Self {
             length: 0,
             next: None,
+            pool: NodePool::with_capacity(capacity),
         }
     }
 
+    pub fn shrink_pool(&mut self) {
+        self.pool.shrink();
+    }
+
+    pub fn pool_stats(&self) -> PoolStats {
+        self.pool.stats()
+    }
+
     pub fn len(&self) -> usize {
         self.length
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
     // 头插法
     pub fn push_front(&mut self, element: T) {
         // 如果直接Node::new()，创建的node只是局部变量（在对应的栈上），一旦运行方法结束，就会回收，这样的话，self.next将会指向一个无意义的地址，引发内存安全问题，所以使用Box将它放在堆上，这样就不会被回收了
-        let mut node = Box::new(Node::new(element));
+        let mut node = self.pool.alloc(Node::new(element));
         node.next = self.next;
         // Box::into_raw()将Box<T>转换成指针*mut T，同时转移所有权
         self.next = NonNull::new(Box::into_raw(node));
@@ -63,12 +90,9 @@ impl<T: std::fmt::Debug> SinglyLinkedList<T> {
 
     // 尾插法
     pub fn push(&mut self, element: T) {
-        let node = Box::new(Node::new(element));
+        let node = self.pool.alloc(Node::new(element));
 
-        if self.next.is_none() {
-            self.next = NonNull::new(Box::into_raw(node));
-        } else {
-            let mut current_ptr = self.next.unwrap();
+        if let Some(mut current_ptr) = self.next {
             loop {
                 // as_mut() 是 Rust 中 NonNull 类型的一个方法，用于将 NonNull<T> 转换为可变引用 &mut T。
                 let current_node = unsafe { current_ptr.as_mut() };
@@ -80,6 +104,8 @@ impl<T: std::fmt::Debug> SinglyLinkedList<T> {
                     }
                 }
             }
+        } else {
+            self.next = NonNull::new(Box::into_raw(node));
         }
         self.length += 1;
     }
@@ -95,7 +121,7 @@ impl<T: std::fmt::Debug> SinglyLinkedList<T> {
             self.push(element);
             Ok(())
         } else {
-            let mut node = Box::new(Node::new(element));
+            let mut node = self.pool.alloc(Node::new(element));
 
             // 添加空指针判断
             if self.next.is_none() {
@@ -122,7 +148,7 @@ impl<T: std::fmt::Debug> SinglyLinkedList<T> {
 
     // 按位置删除元素
     pub fn delete(&mut self, pos: usize) -> Result<(), &'static str> {
-        if self.len() == 0 {
+        if self.is_empty() {
             return Err("No elements in List!");
         }
         if pos < 1 || pos > self.len() {
@@ -134,6 +160,7 @@ impl<T: std::fmt::Debug> SinglyLinkedList<T> {
             // from_raw是所有权交给Box，在Box就会自动调用drop，离开了域就会被销毁，into_raw是所有权交出去，因为box没有所有权也就无法调用drop了
             let head_node = unsafe { Box::from_raw(head_ptr.as_ptr()) };
             self.next = head_node.next;
+            self.pool.recycle(head_node);
             self.length -= 1;
             Ok(())
         } else {
@@ -149,6 +176,7 @@ impl<T: std::fmt::Debug> SinglyLinkedList<T> {
             // 令prev_node的next指向target_ptr节点的下一个节点
             let target_node = unsafe { Box::from_raw(target_ptr.as_ptr()) };
             prev_node.next = target_node.next;
+            self.pool.recycle(target_node);
             self.length -= 1;
             Ok(())
         }
@@ -314,4 +342,24 @@ mod tests {
         assert_eq!(*list.get(1).unwrap(), 1);
         assert_eq!(*list.get(2).unwrap(), 3);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_pool_reuses_nodes() {
+        let mut list = SinglyLinkedList::with_pool_capacity(4);
+        for i in 0..4 {
+            list.push(i);
+        }
+        list.delete(2).unwrap();
+        list.delete(1).unwrap();
+        assert_eq!(list.pool_stats().pooled, 2);
+        list.push_front(10);
+        list.insert(11, 2).unwrap();
+        assert_eq!(list.pool_stats().reuses, 2);
+        assert_eq!(list.pool_stats().allocations, 4);
+        assert_eq!(*list.get(1).unwrap(), 10);
+        assert_eq!(*list.get(2).unwrap(), 11);
+        assert_eq!(*list.get(3).unwrap(), 2);
+        list.shrink_pool();
+        assert_eq!(list.pool_stats().pooled, 0);
+    }
+}
@@ -0,0 +1,234 @@
+// 栈的公共接口，SequentialStack、LinkedStack 以及 SqDoubleStack 的每一侧都实现了它，算法可以对具体的栈类型泛型
+// 错误类型和各个栈自身的方法保持一致，使用 &'static str
+pub trait Stack<T> {
+    fn push(&mut self, element: T) -> Result<(), &'static str>;
+
+    fn pop(&mut self) -> Result<T, &'static str>;
+
+    // 查看栈顶元素，不出栈
+    fn peek(&self) -> Result<&T, &'static str>;
+
+    fn peek_mut(&mut self) -> Result<&mut T, &'static str>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // 最多能容纳的元素个数，None 表示没有上限（例如链栈）
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
+}
+
+// 一致性测试用到的检查函数，由 stack_conformance_tests! 宏调用，传入的栈必须为空，并且容量至少为 8
+#[doc(hidden)]
+pub mod conformance {
+    use super::Stack;
+    use crate::linear_structure::test_rng::TestRng;
+
+    pub fn starts_empty<S: Stack<i32> + ?Sized>(stack: &mut S) {
+        assert_eq!(stack.len(), 0);
+        assert!(stack.is_empty());
+        assert!(stack.pop().is_err());
+        assert!(stack.peek().is_err());
+        assert!(stack.peek_mut().is_err());
+        if let Some(capacity) = stack.capacity() {
+            assert!(capacity >= 8, "conformance tests need a capacity of at least 8");
+        }
+    }
+
+    pub fn lifo_order<S: Stack<i32> + ?Sized>(stack: &mut S) {
+        for i in 1..=8 {
+            stack.push(i).unwrap();
+            assert_eq!(stack.len(), i as usize);
+        }
+        for i in (1..=8).rev() {
+            assert_eq!(stack.pop().unwrap(), i);
+        }
+        assert!(stack.is_empty());
+        assert!(stack.pop().is_err());
+    }
+
+    pub fn peek_does_not_remove<S: Stack<i32> + ?Sized>(stack: &mut S) {
+        stack.push(1).unwrap();
+        stack.push(2).unwrap();
+        assert_eq!(*stack.peek().unwrap(), 2);
+        assert_eq!(*stack.peek().unwrap(), 2);
+        assert_eq!(stack.len(), 2);
+        assert_eq!(stack.pop().unwrap(), 2);
+        assert_eq!(*stack.peek().unwrap(), 1);
+        assert_eq!(stack.pop().unwrap(), 1);
+        assert!(stack.peek().is_err());
+    }
+
+    pub fn peek_mut_modifies_top<S: Stack<i32> + ?Sized>(stack: &mut S) {
+        stack.push(1).unwrap();
+        stack.push(2).unwrap();
+        *stack.peek_mut().unwrap() += 40;
+        assert_eq!(stack.len(), 2);
+        assert_eq!(stack.pop().unwrap(), 42);
+        assert_eq!(stack.pop().unwrap(), 1);
+    }
+
+    // 和 Vec 做对照，随机交替地 push 和 pop
+    pub fn matches_vec_model<S: Stack<i32> + ?Sized>(stack: &mut S) {
+        let mut model = Vec::new();
+        let mut rng = TestRng::new(12345);
+        for i in 0..200 {
+            let seed = rng.next_u32();
+            if !(seed >> 16).is_multiple_of(3) && model.len() < 8 {
+                stack.push(i).unwrap();
+                model.push(i);
+            } else {
+                assert_eq!(stack.pop().ok(), model.pop());
+            }
+            assert_eq!(stack.len(), model.len());
+            assert_eq!(stack.is_empty(), model.is_empty());
+            assert_eq!(stack.peek().ok(), model.last());
+        }
+        while let Some(expected) = model.pop() {
+            assert_eq!(stack.pop().unwrap(), expected);
+        }
+        assert!(stack.is_empty());
+    }
+
+    // 有容量上限的栈，满了之后 push 失败且不改变栈
+    pub fn respects_capacity<S: Stack<i32> + ?Sized>(stack: &mut S) {
+        match stack.capacity() {
+            Some(capacity) => {
+                for i in 0..capacity {
+                    stack.push(i as i32).unwrap();
+                }
+                assert!(stack.push(-1).is_err());
+                assert_eq!(stack.len(), capacity);
+                assert_eq!(*stack.peek().unwrap(), capacity as i32 - 1);
+                for i in (0..capacity).rev() {
+                    assert_eq!(stack.pop().unwrap(), i as i32);
+                }
+            }
+            None => {
+                for i in 0..1000 {
+                    stack.push(i).unwrap();
+                }
+                assert_eq!(stack.len(), 1000);
+                while stack.pop().is_ok() {}
+            }
+        }
+        assert!(stack.is_empty());
+    }
+}
+
+// 为某个栈生成一组一致性测试
+// stack_conformance_tests!(linked, LinkedStack::<i32>::new());
+// 需要借用的视图可以先声明被借用的变量：
+// stack_conformance_tests!(left, let stack = SqDoubleStack::<i32, 18>::new(); stack.side_mut(StackNum::First));
+#[macro_export]
+macro_rules! stack_conformance_tests {
+    ($name:ident, let $owner:ident = $make:expr; $view:expr) => {
+        mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #[test]
+            fn starts_empty() {
+                let mut $owner = $make;
+                $crate::linear_structure::stack::conformance::starts_empty(&mut $view);
+            }
+
+            #[test]
+            fn lifo_order() {
+                let mut $owner = $make;
+                $crate::linear_structure::stack::conformance::lifo_order(&mut $view);
+            }
+
+            #[test]
+            fn peek_does_not_remove() {
+                let mut $owner = $make;
+                $crate::linear_structure::stack::conformance::peek_does_not_remove(&mut $view);
+            }
+
+            #[test]
+            fn peek_mut_modifies_top() {
+                let mut $owner = $make;
+                $crate::linear_structure::stack::conformance::peek_mut_modifies_top(&mut $view);
+            }
+
+            #[test]
+            fn matches_vec_model() {
+                let mut $owner = $make;
+                $crate::linear_structure::stack::conformance::matches_vec_model(&mut $view);
+            }
+
+            #[test]
+            fn respects_capacity() {
+                let mut $owner = $make;
+                $crate::linear_structure::stack::conformance::respects_capacity(&mut $view);
+            }
+        }
+    };
+    ($name:ident, $make:expr) => {
+        $crate::stack_conformance_tests!($name, let stack = $make; stack);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linear_structure::{LinkedStack, MultiStack, SequentialStack, SqDoubleStack, multi_stack::Strategy, sequential_double_stack::StackNum};
+
+    crate::stack_conformance_tests!(sequential_stack, SequentialStack::<i32, 16>::new());
+    crate::stack_conformance_tests!(linked_stack, LinkedStack::<i32>::default());
+    crate::stack_conformance_tests!(double_stack_first, let stack = SqDoubleStack::<i32, 18>::new(); stack.side_mut(StackNum::First));
+    crate::stack_conformance_tests!(double_stack_second, let stack = SqDoubleStack::<i32, 18>::new(); stack.side_mut(StackNum::Second));
+    crate::stack_conformance_tests!(split_left, let stack = SqDoubleStack::<i32, 18>::new(); stack.split_mut().0);
+    crate::stack_conformance_tests!(split_right, let stack = SqDoubleStack::<i32, 18>::new(); stack.split_mut().1);
+    crate::stack_conformance_tests!(multi_stack_partitioned, let stacks = MultiStack::<i32, 24, 3>::new(); stacks.stack_mut(1));
+    crate::stack_conformance_tests!(multi_stack_linked, let stacks = MultiStack::<i32, 24, 3>::with_strategy(Strategy::Linked); stacks.stack_mut(2));
+
+    // 对栈类型泛型的算法
+    fn reverse_with<S: Stack<char>>(mut stack: S, text: &str) -> String {
+        for c in text.chars() {
+            stack.push(c).unwrap();
+        }
+        let mut result = String::new();
+        while let Ok(c) = stack.pop() {
+            result.push(c);
+        }
+        result
+    }
+
+    #[test]
+    fn test_generic_algorithm() {
+        assert_eq!(reverse_with(SequentialStack::<char, 8>::new(), "stack"), "kcats");
+        assert_eq!(reverse_with(LinkedStack::default(), "stack"), "kcats");
+        let mut double: SqDoubleStack<char, 16> = SqDoubleStack::new();
+        assert_eq!(reverse_with(double.side_mut(StackNum::Second), "stack"), "kcats");
+    }
+
+    #[test]
+    fn test_double_stack_sides_share_space() {
+        let mut double: SqDoubleStack<i32, 6> = SqDoubleStack::new();
+        double.side_mut(StackNum::First).push(1).unwrap();
+        let mut second = double.side_mut(StackNum::Second);
+        assert_eq!(second.capacity(), Some(5));
+        second.push(2).unwrap();
+        assert_eq!(second.len(), 1);
+        let first = double.side_mut(StackNum::First);
+        assert_eq!(first.len(), 1);
+        assert_eq!(first.capacity(), Some(5));
+        assert_eq!(*first.peek().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_dyn_stack() {
+        let mut stacks: Vec<Box<dyn Stack<i32>>> = vec![Box::new(SequentialStack::<i32, 4>::new()), Box::new(LinkedStack::default())];
+        for stack in stacks.iter_mut() {
+            stack.push(7).unwrap();
+            assert_eq!(stack.pop().unwrap(), 7);
+        }
+        assert_eq!(stacks[0].capacity(), Some(4));
+        assert_eq!(stacks[1].capacity(), None);
+    }
+}
@@ -0,0 +1,17 @@
+// 测试共用的伪随机数生成器，固定种子保证每次运行的操作序列相同，失败时可以复现
+// stack::conformance 在非测试构建中也会编译，所以这里不加 #[cfg(test)]，但只在 crate 内部可见
+// 32 位线性同余，低位周期短，调用方取高位使用
+
+pub(crate) struct TestRng(u32);
+
+impl TestRng {
+    pub(crate) const fn new(seed: u32) -> Self {
+        Self(seed)
+    }
+
+    // 返回完整的 32 位状态
+    pub(crate) fn next_u32(&mut self) -> u32 {
+        self.0 = self.0.wrapping_mul(1103515245).wrapping_add(12345);
+        self.0
+    }
+}
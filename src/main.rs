@@ -7,12 +7,76 @@
  * @Description: 这是默认设置,请设置`customMade`, 打开koroFileHeader查看配置 进行设置: https://github.com/OBKoro1/koro1FileHeader/wiki/%E9%85%8D%E7%BD%AE
  */
 
-use crate::linear_structure::fibonacci;
+use std::{env, process};
 
-pub mod linear_structure;
+use rust_data_struct::linear_structure::{
+    algorithms::{
+        call_tree::{self, Tracer},
+        maze::Maze,
+    },
+    fibonacci,
+};
 
 fn main() {
-    let n = 13;
-    let result = fibonacci(n);
-    println!("{}", result.unwrap());
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        // cargo run -- maze <file> [--all-paths]
+        Some("maze") => run_maze(&args[1..]),
+        // cargo run -- trace <n> [--dot | --json] [--memo]
+        Some("trace") => run_trace(&args[1..]),
+        _ => {
+            let n = 13;
+            let result = fibonacci(n);
+            println!("{}", result.unwrap());
+        }
+    }
+}
+
+// 打印朴素递归斐波那契的调用树
+fn run_trace(args: &[String]) {
+    let Some(n) = args.iter().find(|a| !a.starts_with("--")).and_then(|a| a.parse::<u64>().ok()) else {
+        eprintln!("usage: trace <n> [--dot | --json] [--memo]");
+        process::exit(2);
+    };
+    let fib = |tracer: &mut Tracer<'_, u64, u64>, n: u64| if n < 2 { n } else { tracer.call(n - 1) + tracer.call(n - 2) };
+    let tree = if args.iter().any(|a| a == "--memo") { call_tree::trace_memoized("fib", n, fib) } else { call_tree::trace("fib", n, fib) };
+    if args.iter().any(|a| a == "--dot") {
+        print!("{}", tree.to_dot());
+    } else if args.iter().any(|a| a == "--json") {
+        println!("{}", tree.to_json());
+    } else {
+        print!("{}", tree.to_text());
+        println!("{}", tree.stats());
+    }
+}
+
+fn run_maze(args: &[String]) {
+    let all_paths = args.iter().any(|a| a == "--all-paths");
+    let Some(file) = args.iter().find(|a| !a.starts_with("--")) else {
+        eprintln!("usage: maze <file> [--all-paths]");
+        process::exit(2);
+    };
+    let maze = match Maze::from_file(file) {
+        Ok(maze) => maze,
+        Err(e) => {
+            eprintln!("{}: {}", file, e);
+            process::exit(1);
+        }
+    };
+    if all_paths {
+        let mut count = 0;
+        for path in maze.all_paths() {
+            count += 1;
+            println!("path {} (length {}):\n{}\n", count, path.len(), maze.render(&path));
+        }
+        println!("{} path(s) found", count);
+    } else {
+        match maze.solve() {
+            Some(path) => println!("{}", maze.render(&path)),
+            None => {
+                eprintln!("no path from S to E");
+                process::exit(1);
+            }
+        }
+    }
 }
@@ -0,0 +1,74 @@
+// 以外部使用者的身份测试 LinkedStack 的公开接口
+
+use rust_data_struct::linear_structure::{LinkedStack, Stack};
+
+#[test]
+fn test_basic_operations() {
+    let mut stack = LinkedStack::new();
+    assert!(stack.is_empty());
+    assert_eq!(stack.len(), 0);
+    assert_eq!(stack.peek(), Err("Stack is empty!"));
+
+    stack.push(1);
+    stack.push(2);
+    stack.push(3);
+    assert_eq!(stack.len(), 3);
+    assert_eq!(*stack.peek().unwrap(), 3);
+    *stack.peek_mut().unwrap() = 30;
+    assert_eq!(stack.pop(), Ok(30));
+    assert_eq!(stack.pop(), Ok(2));
+    assert_eq!(*stack.get().unwrap(), 1);
+
+    stack.clear();
+    assert!(stack.is_empty());
+    assert_eq!(stack.pop(), Err("Stack is empty!"));
+}
+
+#[test]
+fn test_iterators() {
+    let stack: LinkedStack<i32> = (1..=4).collect();
+    assert_eq!(stack.iter().copied().collect::<Vec<_>>(), vec![4, 3, 2, 1]);
+    assert_eq!(stack.iter().len(), 4);
+
+    let mut total = 0;
+    for element in &stack {
+        total += element;
+    }
+    assert_eq!(total, 10);
+    assert_eq!(stack.into_iter().collect::<Vec<_>>(), vec![4, 3, 2, 1]);
+}
+
+#[test]
+fn test_extend_and_debug() {
+    let mut stack = LinkedStack::default();
+    stack.extend(["a", "b"]);
+    stack.extend(vec!["c"]);
+    assert_eq!(format!("{:?}", stack), r#"["c", "b", "a"]"#);
+    assert_eq!(format!("{:?}", LinkedStack::<u8>::new()), "[]");
+}
+
+#[test]
+fn test_through_stack_trait() {
+    fn fill<S: Stack<String>>(stack: &mut S) {
+        for word in ["x", "y"] {
+            stack.push(word.to_string()).unwrap();
+        }
+    }
+
+    let mut stack = LinkedStack::new();
+    fill(&mut stack);
+    assert_eq!(Stack::capacity(&stack), None);
+    assert_eq!(stack.pop().unwrap(), "y");
+    assert_eq!(Stack::len(&stack), 1);
+}
+
+#[test]
+fn test_long_stack_drop() {
+    // 一百万个结点，drop 和 clear 都不能递归释放
+    let mut stack: LinkedStack<usize> = (0..1_000_000).collect();
+    assert_eq!(stack.len(), 1_000_000);
+    assert_eq!(stack.peek(), Ok(&999_999));
+    stack.clear();
+    stack.extend(0..1_000_000);
+    drop(stack);
+}